@@ -0,0 +1,92 @@
+/// Solve module: run question solver over testcase and judge its answer
+/// define FnSolver, Verdict
+use lib::io::input::{InputError, TcFileInput};
+use std::fmt::Display;
+
+/// Question solver: read testcase input, return answer lines.
+///   malformed input is returned as InputError, not a wrong answer.
+pub type FnSolver = fn(input: &mut TcFileInput) -> Result<Vec<String>, InputError>;
+
+/// Verdict:
+/// result of solver over one testcase
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Answered(Vec<String>), // no expected answer to compare with
+    Accepted,
+    WrongAnswer {
+        expect: Vec<String>,
+        actual: Vec<String>,
+    },
+    MalformedInput(InputError),
+}
+
+impl Verdict {
+    /// Whether the testcase is failed
+    pub fn is_failed(&self) -> bool {
+        matches!(
+            self,
+            Verdict::WrongAnswer { .. } | Verdict::MalformedInput(_)
+        )
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Answered(_) => write!(f, "answered"),
+            Verdict::Accepted => write!(f, "accepted"),
+            Verdict::WrongAnswer { .. } => write!(f, "wrong answer"),
+            Verdict::MalformedInput(e) => write!(f, "malformed input: {}", e),
+        }
+    }
+}
+
+/// judge() run solver over input, then compare answer with expected lines if any.
+pub fn judge(solver: FnSolver, input: &mut TcFileInput, expect: Option<&[String]>) -> Verdict {
+    let actual = match solver(input) {
+        Ok(actual) => actual,
+        Err(e) => return Verdict::MalformedInput(e),
+    };
+    match expect {
+        None => Verdict::Answered(actual),
+        Some(expect) if expect == actual => Verdict::Accepted,
+        Some(expect) => Verdict::WrongAnswer {
+            expect: expect.to_vec(),
+            actual,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sum_solver(input: &mut TcFileInput) -> Result<Vec<String>, InputError> {
+        let n = input.read_int()?;
+        let sum: i32 = input.read_vec::<i32>()?.iter().sum();
+        Ok(vec![(n + sum).to_string()])
+    }
+
+    #[test]
+    fn judge_verdicts() {
+        let load = || TcFileInput::load_testfile("test_input.tc").unwrap();
+        let expect = ["119".to_string()];
+        assert_eq!(
+            judge(sum_solver, &mut load(), None),
+            Verdict::Answered(expect.to_vec())
+        );
+        let verdict = judge(sum_solver, &mut load(), Some(&["0".to_string()]));
+        assert!(verdict.is_failed());
+        assert_eq!(verdict.to_string(), "wrong answer");
+
+        let mut input = load();
+        assert_eq!(
+            judge(sum_solver, &mut input, Some(&expect)),
+            Verdict::Accepted
+        );
+        let verdict = judge(sum_solver, &mut input, Some(&expect));
+        assert!(matches!(&verdict, Verdict::MalformedInput(e) if e.line == 4 && e.col == 1));
+        assert!(verdict.is_failed());
+        assert!(verdict.to_string().starts_with("malformed input: "));
+    }
+}
@@ -1,2 +1,2 @@
 pub mod question;
-
+pub mod spec;
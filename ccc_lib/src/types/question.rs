@@ -1,5 +1,6 @@
 /// Question module: question meta datas
 /// define struct Question, QuestionType
+use crate::types::spec::{InputSpec, SpecError};
 use std::fmt::Display;
 
 /// QuestionType:
@@ -27,6 +28,7 @@ impl Display for QuestionType {
 ///   - num: index in the exam
 ///   - title: question's title
 ///   - description: question's description
+///   - input_spec: input format of testcases, see InputSpec
 /// ## Samples
 ///   Question::new(2015, QuestionType::default(), 3, "HelloWorld".to_string())
 /// ## References
@@ -38,6 +40,7 @@ pub struct Question {
     pub num: u8,
     pub title: String,
     pub description: String,
+    pub input_spec: Option<InputSpec>,
 }
 
 impl Question {
@@ -49,6 +52,7 @@ impl Question {
             num,
             title,
             description: "".to_string(),
+            input_spec: None,
         }
     }
 
@@ -56,6 +60,12 @@ impl Question {
     pub fn define(&mut self, description: &str) {
         self.description.push_str(description);
     }
+
+    /// Question::specify() parse input spec text to Question::input_spec.
+    pub fn specify(&mut self, spec: &str) -> Result<(), SpecError> {
+        self.input_spec = Some(InputSpec::parse(spec)?);
+        Ok(())
+    }
 }
 
 /// ## Display
@@ -112,6 +122,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn question_specify() {
+        let mut q = new_default_question();
+        assert!(q.input_spec.is_none());
+        q.specify("line n:int[1..]").unwrap();
+        assert_eq!(q.input_spec.as_ref().unwrap().lines.len(), 1);
+        assert!(q.specify("line n").is_err());
+    }
+
     #[test]
     fn question_type_display() {
         let qt = QuestionType::Junior;
@@ -121,6 +140,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn question_type_in_question() {
         let q = new_default_question();
         match q.level {
@@ -0,0 +1,529 @@
+/// Input spec module: declarative input format of question, and its validator
+/// define InputSpec, LineSpec, TokenSpec, TokenKind, Count, SpecError
+///
+/// Spec language, a statement in each line, line starts with # is a comment:
+///   line  TOKEN...              one input line of tokens
+///   lines COUNT TOKEN...        COUNT input lines of tokens
+///   grid  COUNT COUNT "ALPHA"   rows of exactly cols chars in alphabet
+///   TOKEN: name:type[min..max]*COUNT
+///          type is int, float or word. range bounds are inclusive and optional,
+///          *COUNT repeats the token in the line.
+///   COUNT: number, or name of an int token in earlier `line`.
+/// Sample(03s3 floor plan):
+///   line wood:int[0..]
+///   line rows:int[1..25]
+///   line cols:int[1..25]
+///   grid rows cols "I."
+///
+use lib::io::input::{InputError, TcFileInput};
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// Count of lines, tokens or grid chars
+#[derive(Debug, Clone, PartialEq)]
+pub enum Count {
+    Fixed(usize),
+    Var(String), // value of earlier int token
+}
+
+impl Display for Count {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Count::Fixed(n) => write!(f, "{}", n),
+            Count::Var(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Token type with inclusive range
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    Int(Option<i64>, Option<i64>),
+    Float(Option<f64>, Option<f64>),
+    Word,
+}
+
+impl Display for TokenKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn bound<T: Display>(b: &Option<T>) -> String {
+            b.as_ref().map(|b| b.to_string()).unwrap_or_default()
+        }
+        match self {
+            TokenKind::Int(None, None) => write!(f, "int"),
+            TokenKind::Int(min, max) => write!(f, "int[{}..{}]", bound(min), bound(max)),
+            TokenKind::Float(None, None) => write!(f, "float"),
+            TokenKind::Float(min, max) => write!(f, "float[{}..{}]", bound(min), bound(max)),
+            TokenKind::Word => write!(f, "word"),
+        }
+    }
+}
+
+/// Named token in an input line
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenSpec {
+    pub name: String,
+    pub kind: TokenKind,
+    pub repeat: Option<Count>,
+}
+
+impl Display for TokenSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.name, self.kind)
+    }
+}
+
+/// Statement of spec
+#[derive(Debug, Clone, PartialEq)]
+pub enum LineSpec {
+    Line(Vec<TokenSpec>),
+    Lines(Count, Vec<TokenSpec>),
+    Grid {
+        rows: Count,
+        cols: Count,
+        alphabet: String,
+    },
+}
+
+/// Spec syntax error, line is 1-based line in spec text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpecError {
+    pub line: usize,
+    pub reason: String,
+}
+
+impl Display for SpecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "input spec line {}: {}", self.line, self.reason)
+    }
+}
+
+/// Input format of question
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InputSpec {
+    pub lines: Vec<LineSpec>,
+}
+
+impl InputSpec {
+    /// Parse spec text
+    pub fn parse(text: &str) -> Result<InputSpec, SpecError> {
+        let mut spec = InputSpec::default();
+        let mut names: Vec<(String, bool)> = Vec::new(); // token name, usable as count
+        for (idx, stmt) in text.lines().enumerate() {
+            let stmt = stmt.trim();
+            if stmt.is_empty() || stmt.starts_with('#') {
+                continue;
+            }
+            let error = |reason: String| SpecError {
+                line: idx + 1,
+                reason,
+            };
+            let line = InputSpec::parse_stmt(stmt, &names).map_err(error)?;
+            let tokens = match &line {
+                LineSpec::Line(tokens) => tokens.as_slice(),
+                LineSpec::Lines(_, tokens) => tokens.as_slice(),
+                LineSpec::Grid { .. } => &[],
+            };
+            for token in tokens {
+                if names.iter().any(|(name, _)| *name == token.name) {
+                    return Err(error(format!("duplicated token '{}'", token.name)));
+                }
+                let countable = matches!(line, LineSpec::Line(_))
+                    && matches!(token.kind, TokenKind::Int(..))
+                    && token.repeat.is_none();
+                names.push((token.name.clone(), countable));
+            }
+            spec.lines.push(line);
+        }
+        Ok(spec)
+    }
+
+    fn parse_stmt(stmt: &str, names: &[(String, bool)]) -> Result<LineSpec, String> {
+        let (keyword, rest) = stmt.split_once(char::is_whitespace).unwrap_or((stmt, ""));
+        let mut words = rest.split_whitespace();
+        let mut count = || match words.next() {
+            Some(word) => parse_count(word, names),
+            None => Err(format!("count required after '{}'", keyword)),
+        };
+        match keyword {
+            "line" => Ok(LineSpec::Line(parse_tokens(rest, names)?)),
+            "lines" => {
+                let count = count()?;
+                let rest = rest.trim_start().split_once(char::is_whitespace);
+                Ok(LineSpec::Lines(
+                    count,
+                    parse_tokens(rest.map(|(_, r)| r).unwrap_or_default(), names)?,
+                ))
+            }
+            "grid" => {
+                let (rows, cols) = (count()?, count()?);
+                let alphabet = words.collect::<Vec<_>>().join(" ");
+                match alphabet.strip_prefix('"').and_then(|a| a.strip_suffix('"')) {
+                    Some(alphabet) if !alphabet.is_empty() => Ok(LineSpec::Grid {
+                        rows,
+                        cols,
+                        alphabet: alphabet.to_string(),
+                    }),
+                    _ => Err(format!("quoted alphabet required, found '{}'", alphabet)),
+                }
+            }
+            _ => Err(format!("unknown statement '{}'", keyword)),
+        }
+    }
+
+    /// Check testcase input against spec, the first violation is returned.
+    pub fn validate(&self, input: &mut TcFileInput) -> Result<(), InputError> {
+        let mut vars: HashMap<&str, i64> = HashMap::new();
+        for line in &self.lines {
+            match line {
+                LineSpec::Line(tokens) => {
+                    for (token, value) in check_line(input, tokens, &vars)? {
+                        vars.insert(&token.name, value);
+                    }
+                }
+                LineSpec::Lines(count, tokens) => {
+                    for _ in 0..count_value(input, count, &vars)? {
+                        check_line(input, tokens, &vars)?;
+                    }
+                }
+                LineSpec::Grid {
+                    rows,
+                    cols,
+                    alphabet,
+                } => {
+                    let (rows, cols) = (
+                        count_value(input, rows, &vars)?,
+                        count_value(input, cols, &vars)?,
+                    );
+                    let expect = format!("{} chars of '{}'", cols, alphabet);
+                    for _ in 0..rows {
+                        let row = input.next_line(&expect)?;
+                        if row.chars().count() != cols {
+                            return Err(input.input_error(0, &expect, Some(&row)));
+                        }
+                        let bad = row.chars().position(|c| !alphabet.contains(c));
+                        if let Some(idx) = bad {
+                            let found = row.chars().nth(idx).map(String::from);
+                            let expect = format!("char of '{}'", alphabet);
+                            return Err(input.input_error(idx + 1, &expect, found.as_deref()));
+                        }
+                    }
+                }
+            }
+        }
+        // a read error is reported as in next_line, not taken as end of input
+        while !input.file_handle.is_eof().map_err(|e| {
+            input.input_error(0, "end of input", Some(&format!("read error: {}", e)))
+        })? {
+            let line = input.next_line("end of input")?;
+            if !line.trim().is_empty() {
+                return Err(input.input_error(0, "end of input", Some(&line)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Tokens separated by whitespace
+fn parse_tokens(text: &str, names: &[(String, bool)]) -> Result<Vec<TokenSpec>, String> {
+    let tokens: Vec<TokenSpec> = text
+        .split_whitespace()
+        .map(|word| parse_token(word, names))
+        .collect::<Result<_, _>>()?;
+    if tokens.is_empty() {
+        return Err("tokens required".to_string());
+    }
+    Ok(tokens)
+}
+
+/// name:type[min..max]*count
+fn parse_token(word: &str, names: &[(String, bool)]) -> Result<TokenSpec, String> {
+    let (name, rest) = word
+        .split_once(':')
+        .ok_or_else(|| format!("expect name:type, found '{}'", word))?;
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!("invalid token name '{}'", name));
+    }
+    let (rest, repeat) = match rest.split_once('*') {
+        Some((rest, count)) => (rest, Some(parse_count(count, names)?)),
+        None => (rest, None),
+    };
+    let (kind, range) = match rest.split_once('[') {
+        Some((kind, range)) => {
+            let range = range
+                .strip_suffix(']')
+                .and_then(|r| r.split_once(".."))
+                .ok_or_else(|| format!("expect [min..max], found '[{}'", range))?;
+            (kind, Some(range))
+        }
+        None => (rest, None),
+    };
+    let (min, max) = range.unwrap_or_default();
+    let kind = match kind {
+        "int" => TokenKind::Int(parse_bound(min)?, parse_bound(max)?),
+        "float" => TokenKind::Float(parse_bound(min)?, parse_bound(max)?),
+        "word" if range.is_none() => TokenKind::Word,
+        "word" => return Err(format!("word token '{}' has no range", name)),
+        _ => {
+            return Err(format!(
+                "unknown type '{}', expect int, float or word",
+                kind
+            ))
+        }
+    };
+    Ok(TokenSpec {
+        name: name.to_string(),
+        kind,
+        repeat,
+    })
+}
+
+fn parse_bound<T: std::str::FromStr>(bound: &str) -> Result<Option<T>, String> {
+    if bound.is_empty() {
+        return Ok(None);
+    }
+    bound
+        .parse()
+        .map(Some)
+        .map_err(|_| format!("invalid range bound '{}'", bound))
+}
+
+fn parse_count(word: &str, names: &[(String, bool)]) -> Result<Count, String> {
+    if let Ok(n) = word.parse() {
+        return Ok(Count::Fixed(n));
+    }
+    match names.iter().find(|(name, _)| name == word) {
+        Some((_, true)) => Ok(Count::Var(word.to_string())),
+        Some(_) => Err(format!("'{}' is not a single int token of line", word)),
+        None => Err(format!("unknown count '{}'", word)),
+    }
+}
+
+/// Value of count, error at the last read line if it is negative
+fn count_value(
+    input: &TcFileInput,
+    count: &Count,
+    vars: &HashMap<&str, i64>,
+) -> Result<usize, InputError> {
+    match count {
+        Count::Fixed(n) => Ok(*n),
+        Count::Var(name) => {
+            let value = vars.get(name.as_str()).copied().unwrap_or_default();
+            usize::try_from(value).map_err(|_| {
+                let expect = format!("non-negative count {}", name);
+                input.input_error(0, &expect, Some(&value.to_string()))
+            })
+        }
+    }
+}
+
+/// Check next line against tokens, return int values of single tokens.
+fn check_line<'a>(
+    input: &mut TcFileInput,
+    tokens: &'a [TokenSpec],
+    vars: &HashMap<&str, i64>,
+) -> Result<Vec<(&'a TokenSpec, i64)>, InputError> {
+    let mut repeats = Vec::new(); // words of each token, not expanded as counts come from input
+    for token in tokens {
+        repeats.push(match &token.repeat {
+            Some(count) => count_value(input, count, vars)?,
+            None => 1,
+        });
+    }
+    let total = repeats
+        .iter()
+        .try_fold(0usize, |sum, n| sum.checked_add(*n));
+    let expect = match total {
+        Some(total) => format!("{} tokens", total),
+        None => format!("over {} tokens", usize::MAX),
+    };
+    let line = input.next_line(&expect)?;
+    let words: Vec<&str> = line.split_whitespace().collect();
+    if total != Some(words.len()) {
+        let found = format!("{} tokens", words.len());
+        return Err(input.input_error(0, &expect, Some(&found)));
+    }
+    let layout = tokens
+        .iter()
+        .zip(repeats)
+        .flat_map(|(token, repeat)| std::iter::repeat_n(token, repeat));
+    let mut values = Vec::new();
+    for (idx, (token, word)) in layout.zip(&words).enumerate() {
+        let invalid = || input.input_error(idx + 1, &token.to_string(), Some(word));
+        match token.kind {
+            TokenKind::Int(min, max) => {
+                let value: i64 = word.parse().map_err(|_| invalid())?;
+                if min.is_some_and(|m| value < m) || max.is_some_and(|m| value > m) {
+                    return Err(invalid());
+                }
+                if token.repeat.is_none() {
+                    values.push((token, value));
+                }
+            }
+            TokenKind::Float(min, max) => {
+                let value: f64 = word.parse().map_err(|_| invalid())?;
+                if !value.is_finite()
+                    || min.is_some_and(|m| value < m)
+                    || max.is_some_and(|m| value > m)
+                {
+                    return Err(invalid());
+                }
+            }
+            TokenKind::Word => {}
+        }
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lib::io::provider::memio::MemIoReadProvider;
+    use lib::io::IoReader;
+    use std::io;
+
+    const FLOOR_PLAN: &str = "
+        # 03s3 floor plan
+        line wood:int[0..]
+        line rows:int[1..25]
+        line cols:int[1..25]
+        grid rows cols \"I.\"
+    ";
+
+    fn validate(spec: &str, text: &str) -> Result<(), InputError> {
+        let mut input = TcFileInput::new(MemIoReadProvider::from_text(text), "mem");
+        InputSpec::parse(spec).unwrap().validate(&mut input)
+    }
+
+    #[test]
+    fn spec_parse() {
+        let spec = InputSpec::parse(FLOOR_PLAN).unwrap();
+        assert_eq!(spec.lines.len(), 4);
+        assert_eq!(
+            spec.lines[1],
+            LineSpec::Line(vec![TokenSpec {
+                name: "rows".to_string(),
+                kind: TokenKind::Int(Some(1), Some(25)),
+                repeat: None,
+            }])
+        );
+        let spec = InputSpec::parse("line n:int x:float[..1.5]\nlines n v:int*n s:word").unwrap();
+        let LineSpec::Lines(count, tokens) = &spec.lines[1] else {
+            panic!("lines statement expected");
+        };
+        assert_eq!(*count, Count::Var("n".to_string()));
+        assert_eq!(tokens[0].repeat, Some(Count::Var("n".to_string())));
+        assert_eq!(tokens[0].to_string(), "v:int");
+        assert_eq!(tokens[1].kind, TokenKind::Word);
+    }
+
+    #[test]
+    fn spec_parse_errors() {
+        let error = |text: &str| InputSpec::parse(text).unwrap_err().to_string();
+        assert_eq!(
+            error("line n:int\n\nrow n"),
+            "input spec line 3: unknown statement 'row'"
+        );
+        assert_eq!(
+            error("grid n 3 \"I.\""),
+            "input spec line 1: unknown count 'n'"
+        );
+        assert_eq!(
+            error("line n:int*2\ngrid n n \"I\""),
+            "input spec line 2: 'n' is not a single int token of line"
+        );
+        assert_eq!(
+            error("line n:text"),
+            "input spec line 1: unknown type 'text', expect int, float or word"
+        );
+        assert_eq!(
+            error("line n:int[1-2]"),
+            "input spec line 1: expect [min..max], found '[1-2]'"
+        );
+        assert_eq!(
+            error("line n:int n:word"),
+            "input spec line 1: duplicated token 'n'"
+        );
+        assert_eq!(
+            error("grid 2 2 I."),
+            "input spec line 1: quoted alphabet required, found 'I.'"
+        );
+    }
+
+    #[test]
+    fn validate_floor_plan() {
+        assert_eq!(validate(FLOOR_PLAN, "105\n2\n3\nI.I\n...\n\n"), Ok(()));
+        let err = validate(FLOOR_PLAN, "105\n26\n3\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "mem:2:1: expect rows:int[1..25], found '26'"
+        );
+        let err = validate(FLOOR_PLAN, "105\n2\n3\nI.I\n.x.\n").unwrap_err();
+        assert_eq!(err.to_string(), "mem:5:2: expect char of 'I.', found 'x'");
+        let err = validate(FLOOR_PLAN, "105\n2\n3\nI.I\n..\n").unwrap_err();
+        assert_eq!(err.to_string(), "mem:5: expect 3 chars of 'I.', found '..'");
+        let err = validate(FLOOR_PLAN, "105\n2\n3\nI.I\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "mem:5: expect 3 chars of 'I.', found end of input"
+        );
+        let err = validate(FLOOR_PLAN, "105\n1\n1\nI\nI\n").unwrap_err();
+        assert_eq!(err.to_string(), "mem:5: expect end of input, found 'I'");
+    }
+
+    #[test]
+    fn validate_tokens_and_lines() {
+        let spec = "line n:int[0..] x:float[0..1]\nlines n v:int*n s:word";
+        assert_eq!(validate(spec, "2 0.5\n1 2 a\n3 4 b"), Ok(()));
+        let err = validate(spec, "2 1.5\n").unwrap_err();
+        assert_eq!((err.line, err.col), (1, 2));
+        let err = validate(spec, "2 nan\n").unwrap_err();
+        assert_eq!(err.expect, "x:float[0..1]");
+        let err = validate(spec, "2 0\n1 2 a\n3 b").unwrap_err();
+        assert_eq!(err.to_string(), "mem:3: expect 3 tokens, found '2 tokens'");
+        let err = validate(spec, "2 0\n1 2 a\n3 x b").unwrap_err();
+        assert_eq!(err.to_string(), "mem:3:2: expect v:int, found 'x'");
+        assert_eq!(validate(spec, "0 0\n"), Ok(()));
+        assert_eq!(validate("grid 1 2 \"#.\"", "#.\n"), Ok(()));
+    }
+
+    /// reader failing after its lines
+    struct FailingReader(Box<dyn IoReader>);
+
+    impl IoReader for FailingReader {
+        fn read_bytes(&mut self) -> io::Result<Option<Vec<u8>>> {
+            self.0.read_bytes()
+        }
+
+        fn peek_bytes(&mut self) -> io::Result<Option<&[u8]>> {
+            match self.0.peek_bytes()? {
+                Some(line) => Ok(Some(line)),
+                None => Err(io::Error::other("disk gone")),
+            }
+        }
+    }
+
+    #[test]
+    fn validate_read_error_is_not_eof() {
+        let reader = FailingReader(MemIoReadProvider::from_text("3\n"));
+        let mut input = TcFileInput::new(Box::new(reader), "mem");
+        let spec = InputSpec::parse("line n:int").unwrap();
+        let err = spec.validate(&mut input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "mem:1: expect end of input, found 'read error: disk gone'"
+        );
+    }
+
+    #[test]
+    fn validate_huge_repeat_counts() {
+        let spec = "line n:int\nline v:int*n";
+        let err = validate(spec, "1000000000000000\n1 2 3\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "mem:2: expect 1000000000000000 tokens, found '3 tokens'"
+        );
+        let spec = "line n:int\nline a:int*n b:int*n c:int*n";
+        let err = validate(spec, "9000000000000000000\n1 2 3\n").unwrap_err();
+        assert_eq!(err.expect, format!("over {} tokens", usize::MAX));
+    }
+}
@@ -1,4 +1,5 @@
 use ccc_lib::types::question::{Question, QuestionType};
+use lib::io::input::{InputError, TcFileInput};
 
 /// CCC '03 S3 - Floor Plan
 /// Canadian Computing Competition: 2003 Stage 1, Junior #5, Senior #3
@@ -38,9 +39,8 @@ use ccc_lib::types::question::{Question, QuestionType};
 /// The first line contains the number of square metres of flooring you have. The second line contains an integer in range
 /// that represents the number of rows in the grid. The third line contains an integer
 /// in that represents the number of columns in the grid. The remaining lines contain characters of grid data.
-
 pub fn get_question() -> Question {
-    Question {
+    let mut q = Question {
         year: 2003,
         level: QuestionType::Senior,
         num: 3,
@@ -81,9 +81,135 @@ Sample 1: \
  The first line contains the number of square metres of flooring you have. The second line contains an integer in range\
  that represents the number of rows in the grid. The third line contains an integer\
  in that represents the number of columns in the grid. The remaining lines contain characters of grid data.".to_string(),
+        input_spec: None,
+    };
+    q.specify(INPUT_SPEC).expect("03s3 input spec");
+    q
+}
+
+/// Input format: flooring, rows and cols up to 25, then the floor plan grid.
+const INPUT_SPEC: &str = "
+    line wood:int[0..]
+    line rows:int[1..25]
+    line cols:int[1..25]
+    grid rows cols \"I.\"
+";
+
+/// Solve floor plan: fill the largest rooms first until wood runs out.
+pub fn solve(input: &mut TcFileInput) -> Result<Vec<String>, InputError> {
+    let mut wood = input.read_int()?;
+    let rows = read_dimension(input, "rows")?;
+    let cols = read_dimension(input, "cols")?;
+    let mut plan = Vec::new();
+    for _ in 0..rows {
+        let row: Vec<char> = input.read_str()?.chars().collect();
+        if row.len() != cols || row.iter().any(|c| *c != 'I' && *c != '.') {
+            let expect = format!("{} chars of I or .", cols);
+            return Err(input.input_error(0, &expect, Some(&row.iter().collect::<String>())));
+        }
+        plan.push(row);
+    }
+
+    let mut rooms = room_sizes(&mut plan);
+    rooms.sort_unstable_by(|a, b| b.cmp(a));
+    let mut filled = 0;
+    for size in rooms {
+        if size as i32 > wood {
+            break;
+        }
+        wood -= size as i32;
+        filled += 1;
+    }
+    let unit = if filled == 1 { "room" } else { "rooms" };
+    Ok(vec![format!(
+        "{} {}, {} square metre(s) left over",
+        filled, unit, wood
+    )])
+}
+
+/// Grid dimension in a single line, error if negative.
+fn read_dimension(input: &mut TcFileInput, name: &str) -> Result<usize, InputError> {
+    let n = input.read_int()?;
+    usize::try_from(n).map_err(|_| {
+        let expect = format!("non-negative {}", name);
+        input.input_error(1, &expect, Some(&n.to_string()))
+    })
+}
+
+/// Sizes of rooms(connected . cells), visited cells are marked as wall.
+fn room_sizes(plan: &mut [Vec<char>]) -> Vec<usize> {
+    let mut sizes = Vec::new();
+    for r in 0..plan.len() {
+        for c in 0..plan[r].len() {
+            if plan[r][c] != '.' {
+                continue;
+            }
+            let mut size = 0;
+            let mut stack = vec![(r, c)];
+            plan[r][c] = 'I';
+            while let Some((y, x)) = stack.pop() {
+                size += 1;
+                let nexts = [
+                    (y.wrapping_sub(1), x),
+                    (y + 1, x),
+                    (y, x.wrapping_sub(1)),
+                    (y, x + 1),
+                ];
+                for (ny, nx) in nexts {
+                    if plan.get(ny).and_then(|row| row.get(nx)) == Some(&'.') {
+                        plan[ny][nx] = 'I';
+                        stack.push((ny, nx));
+                    }
+                }
+            }
+            sizes.push(size);
+        }
     }
+    sizes
 }
 
-pub fn run_solver() {
-    println!("CCC '03 S3 - Floor Plan");
+#[cfg(test)]
+mod test {
+    use super::*;
+    use lib::io::provider::memio::MemIoReadProvider;
+
+    #[test]
+    fn solve_sample() {
+        let mut input = TcFileInput::load_testfile("ccc2003s3.tc").unwrap();
+        assert_eq!(
+            solve(&mut input),
+            Ok(vec!["4 rooms, 1 square metre(s) left over".to_string()])
+        );
+    }
+
+    #[test]
+    fn validate_testcases() {
+        let spec = get_question().input_spec.unwrap();
+        let mut input = TcFileInput::load_testfile("ccc2003s3.tc").unwrap();
+        assert_eq!(spec.validate(&mut input), Ok(()));
+        let mut input = TcFileInput::load_testfile("test_input.tc").unwrap();
+        let err = spec.validate(&mut input).unwrap_err();
+        assert_eq!((err.line, err.col), (6, 0));
+        assert_eq!(err.expect, "16 chars of 'I.'");
+    }
+
+    #[test]
+    fn solve_malformed_plan() {
+        let mut input = TcFileInput::load_testfile("test_input.tc").unwrap();
+        let err = solve(&mut input).unwrap_err();
+        assert_eq!((err.line, err.col), (6, 0));
+        assert_eq!(err.expect, "16 chars of I or .");
+
+        let mem = |text: &str| TcFileInput::new(MemIoReadProvider::from_text(text), "mem");
+        let err = solve(&mut mem("10\n-2\n3\n")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "mem:2:1: expect non-negative rows, found '-2'"
+        );
+        let err = solve(&mut mem("10\n1\n3\nI.II\n")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "mem:4: expect 3 chars of I or ., found 'I.II'"
+        );
+    }
 }
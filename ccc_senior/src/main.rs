@@ -1,17 +1,340 @@
+use ccc_lib::dispatch::solve::{judge, Verdict};
 use ccc_lib::types::question::*;
-use std::env;
+use lib::cli::cmd::{CmdExeArgs, Command, ValueSpec};
+use lib::cli::complete::{complete_script, Shell, SHELLS};
+use lib::cli::config::{Config, PROJECT_FILE};
+use lib::cli::doc::{CmdDoc, DocRender, ManDoc, MarkdownDoc};
+use lib::cli::suggest::did_you_mean;
+use lib::cli::value::{ArgValue, QuestionId, ValueType};
+use lib::command_tree;
+use lib::io::debug::{DebugLevel, LogFilter};
+use lib::io::input::TcFileInput;
+use lib::io::locator::{TcLocator, TESTCASE_DIR_ENV};
+use lib::io::log::Logger;
+use lib::io::record::LogFormat;
+use lib::io::sink::LogSink;
+use std::path::PathBuf;
+use std::{env, fs, process};
 
 mod ccc03s3;
 
+/// question ids with solver in this binary
+const QUESTION_IDS: [&str; 1] = ["03s3"];
+
 fn main() {
     let args: Vec<String> = env::args().collect();
-    for (idx, arg) in args.iter().enumerate() {
-        println!("{idx} => {arg}");
+    let rtcmd = match load_commands() {
+        Ok(rtcmd) => rtcmd,
+        Err(e) => {
+            eprintln!("error: {e}");
+            process::exit(2);
+        }
+    };
+    process::exit(rtcmd.exec(args));
+}
+
+/// command tree with config files layer
+fn load_commands() -> Result<Command, String> {
+    let mut rtcmd = build_commands();
+    let config = Config::load().map_err(|e| e.to_string())?;
+    rtcmd.set_config(config).map_err(|e| e.to_string())?;
+    Ok(rtcmd)
+}
+
+/// CCC Senior command tree
+///   ccc_senior [-h] [--dir testcase-dir]
+///   ccc_senior run [question] [-t testcase] [-a answers-file] [--log-json file]
+///   ccc_senior validate question
+///   ccc_senior config show
+///   ccc_senior completions bash|zsh|fish (hidden)
+///   ccc_senior docs man|md [--out dir] (hidden)
+fn build_commands() -> Command {
+    command_tree!(
+        "ccc" => help_cmder, {
+            title: "canada computer competition senior solutions",
+            usage: "ccc [-h] sub-command",
+        },
+        options: [
+            { short: "h", long: "help", title: "help information about application" },
+            {
+                long: "dir",
+                title: "testcase directory, seeked before io_files in workspace root",
+                vspec: ValueSpec::Must,
+                vtype: ValueType::Path,
+                env: TESTCASE_DIR_ENV,
+            },
+        ],
+        subcmds: [
+            (
+                "run" => run_cmder, {
+                    title: "run ccc question solvment",
+                    usage: "ccc run 03s3",
+                    vspec: ValueSpec::Maybe,
+                    vtype: ValueType::QuestionId,
+                },
+                options: [
+                    {
+                        short: "t",
+                        long: "testcase",
+                        title: "testcase file name",
+                        vspec: ValueSpec::Must,
+                        vtype: ValueType::Testcase,
+                        repeat: true,
+                    },
+                    {
+                        short: "a",
+                        long: "answers",
+                        title: "save answers to file, logs go to stderr",
+                        vspec: ValueSpec::Must,
+                        vtype: ValueType::Text,
+                    },
+                    {
+                        long: "log-json",
+                        title: "also save logs and answers to file as JSON lines",
+                        vspec: ValueSpec::Must,
+                        vtype: ValueType::Text,
+                    },
+                ],
+            ),
+            (
+                "validate" => validate_cmder, {
+                    title: "check testcases of question against its input format",
+                    usage: "ccc validate 03s3",
+                    vspec: ValueSpec::Must,
+                    vtype: ValueType::QuestionId,
+                },
+            ),
+            (
+                "config" => help_cmder, {
+                    title: "configuration files",
+                    usage: "ccc config show",
+                },
+                subcmds: [(
+                    "show" => config_show_cmder, {
+                        title: "show effective configuration with source of each key",
+                        usage: "ccc config show",
+                    },
+                )],
+            ),
+            (
+                "completions" => completions_cmder, {
+                    title: "make shell completion script",
+                    usage: "ccc completions bash",
+                    vspec: ValueSpec::Maybe,
+                    vtype: ValueType::Choice(&SHELLS),
+                },
+                options: [{
+                    long: "values",
+                    title: "list dynamic values for completion",
+                    vspec: ValueSpec::Must,
+                    vtype: ValueType::Choice(&["qid", "tc"]),
+                }],
+                hidden: true,
+            ),
+            (
+                "docs" => docs_cmder, {
+                    title: "make man pages or markdown reference",
+                    usage: "ccc docs man --out target/man",
+                    vspec: ValueSpec::Must,
+                    vtype: ValueType::Choice(&["man", "md"]),
+                },
+                options: [{
+                    short: "o",
+                    long: "out",
+                    title: "output directory, print to stdout if absent",
+                    vspec: ValueSpec::Must,
+                    vtype: ValueType::Path,
+                }],
+                hidden: true,
+            ),
+        ],
+    )
+}
+
+/// help of the matched command, eg. "ccc config" shows config's sub commands
+fn help_cmder(cmd_args: CmdExeArgs) -> Result<i32, String> {
+    let rtcmd = build_commands();
+    let cmd = rtcmd.seek_subcmd(&cmd_args.cmd_path()[1..]);
+    for line in cmd.make_doc() {
+        println!("{line}");
+    }
+    Ok(0)
+}
+
+fn run_cmder(cmd_args: CmdExeArgs) -> Result<i32, String> {
+    match cmd_args.token() {
+        None | Some("03s3") => {
+            let q = ccc03s3::get_question();
+            let qid = QuestionId::from_arg("03s3").unwrap();
+            let locator = TcLocator::resolve(cmd_args.get::<PathBuf>("--dir"));
+            let testcases = match cmd_args.opn_values("-t").as_slice() {
+                [] => locator.cases(&qid).into_iter().map(|(_, s)| s).collect(),
+                names => names
+                    .iter()
+                    .map(|name| {
+                        if name.ends_with(".tc") {
+                            locator.locate(name)
+                        } else {
+                            locator.locate_case(&qid, Some(name))
+                        }
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| e.to_string())?,
+            };
+            let mut logger = run_logger(
+                cmd_args.get::<PathBuf>("--answers"),
+                cmd_args.get::<PathBuf>("--log-json"),
+            )?;
+            display_question_summary(q);
+            let mut question = logger.question_span("03s3");
+            let mut failed = 0;
+            for source in testcases {
+                let tc = source.file_name().unwrap_or_default().to_string();
+                let mut case = question.testcase_span(&tc);
+                let mut input = TcFileInput::open_source(&source).map_err(|e| e.to_string())?;
+                let answer = source.answer();
+                let verdict = judge(ccc03s3::solve, &mut input, answer.as_deref());
+                let msg = format!("Testcase: {} => {}", source, verdict);
+                match verdict.is_failed() {
+                    true => case.warn(&msg),
+                    false => case.info(&msg),
+                }
+                if let Verdict::Answered(lines) = &verdict {
+                    lines.iter().for_each(|line| case.answer(line));
+                }
+                failed += i32::from(verdict.is_failed());
+            }
+            drop(question);
+            logger.flush();
+            Ok(failed)
+        }
+        Some(q) => Err(question_not_found(q)),
+    }
+}
+
+/// logger of run: all to stdout, or answers to file and logs to stderr.
+///   all records are saved as JSON lines too if json is given.
+///   logs are filtered by directives in env CCC_LOG, info by default.
+fn run_logger(answers: Option<PathBuf>, json: Option<PathBuf>) -> Result<Logger, String> {
+    let open = |path: &PathBuf, level| {
+        LogSink::file(path, level).map_err(|e| format!("log file '{}': {}", path.display(), e))
+    };
+    let sinks = match &answers {
+        None => vec![LogSink::stdout(DebugLevel::Trace)],
+        Some(path) => vec![
+            LogSink::stderr(DebugLevel::Trace).up_to(DebugLevel::Error),
+            open(path, DebugLevel::Answer)?,
+        ],
+    };
+    let mut logger = Logger::with_sinks(sinks).with_filter(LogFilter::from_env(DebugLevel::Info)?);
+    if let Some(path) = &json {
+        logger.add_sink(open(path, DebugLevel::Trace)?.with_format(LogFormat::Json));
+    }
+    Ok(logger)
+}
+
+fn validate_cmder(cmd_args: CmdExeArgs) -> Result<i32, String> {
+    let id = cmd_args.token().unwrap_or_default();
+    let q = match id {
+        "03s3" => ccc03s3::get_question(),
+        _ => return Err(question_not_found(id)),
+    };
+    let spec = q
+        .input_spec
+        .ok_or(format!("question '{}' has no input spec", id))?;
+    let qid = QuestionId::from_arg(id).unwrap();
+    let locator = TcLocator::resolve(cmd_args.get::<PathBuf>("--dir"));
+    let cases = locator.cases(&qid);
+    if cases.is_empty() {
+        return Err(format!("no testcase of question '{}'", id));
     }
+    let mut failed = 0;
+    for (_, source) in cases {
+        let mut input = TcFileInput::open_source(&source).map_err(|e| e.to_string())?;
+        match spec.validate(&mut input) {
+            Ok(()) => println!("Testcase: {} => valid", source),
+            Err(e) => {
+                println!("Testcase: {} => {}", source, e);
+                failed += 1;
+            }
+        }
+    }
+    Ok(failed)
+}
+
+fn question_not_found(q: &str) -> String {
+    match did_you_mean(q, QUESTION_IDS) {
+        Some(id) => format!("question '{}' not found, did you mean '{}'?", q, id),
+        None => format!("question '{}' not found", q),
+    }
+}
+
+fn config_show_cmder(_cmd_args: CmdExeArgs) -> Result<i32, String> {
+    let rtcmd = load_commands()?;
+    if rtcmd.config().files().is_empty() {
+        println!("# no config file, seeked {} and user config", PROJECT_FILE);
+    }
+    for (path, source) in rtcmd.config().files() {
+        println!("# {}: {}", source, path.display());
+    }
+    let items = rtcmd.effective_config().map_err(|e| e.to_string())?;
+    let width = items.iter().map(|(key, _, _)| key.len()).max().unwrap_or(0);
+    for (key, values, source) in items {
+        println!("{:<width$} = {:<12} ({})", key, values.join(", "), source);
+    }
+    Ok(0)
+}
+
+fn completions_cmder(cmd_args: CmdExeArgs) -> Result<i32, String> {
+    let values = match cmd_args.opn_value("--values") {
+        Some("qid") => QUESTION_IDS.iter().map(|id| id.to_string()).collect(),
+        Some(_) => testcase_names(cmd_args.get::<PathBuf>("--dir")),
+        None => {
+            let shell = cmd_args
+                .get_token::<Shell>()
+                .ok_or("shell name required: bash, zsh or fish")?;
+            let script = complete_script(&build_commands(), shell, env!("CARGO_PKG_NAME"));
+            print!("{script}");
+            return Ok(0);
+        }
+    };
+    for value in values {
+        println!("{value}");
+    }
+    Ok(0)
+}
+
+fn docs_cmder(cmd_args: CmdExeArgs) -> Result<i32, String> {
+    let cmd_doc = CmdDoc::new(&build_commands());
+    let files = match cmd_args.token() {
+        Some("man") => ManDoc::pages(&cmd_doc),
+        _ => vec![("ccc.md".to_string(), MarkdownDoc.render(&cmd_doc))],
+    };
+    for (name, lines) in files {
+        let content = lines.join("\n") + "\n";
+        match cmd_args.get::<PathBuf>("--out") {
+            Some(dir) => fs::write(dir.join(&name), content).map_err(|e| e.to_string())?,
+            None => print!("{content}"),
+        }
+    }
+    Ok(0)
+}
 
-    let q = ccc03s3::get_question();
-    display_question_summary(q);
-    ccc03s3::run_solver();
+/// testcase names accepted by run -t: case names, or file names of testcases without case
+fn testcase_names(dir: Option<PathBuf>) -> Vec<String> {
+    let locator = TcLocator::resolve(dir);
+    let mut names: Vec<String> = QUESTION_IDS
+        .iter()
+        .filter_map(|id| QuestionId::from_arg(id))
+        .flat_map(|qid| locator.cases(&qid))
+        .filter_map(|(name, source)| match name.case {
+            Some(case) => Some(case),
+            None => source.file_name().map(String::from),
+        })
+        .collect();
+    names.sort();
+    names.dedup();
+    names
 }
 
 fn display_question_summary(q: Question) {
@@ -0,0 +1,121 @@
+/// FileIoReadProvider benchmark: loading all lines vs streaming.
+///   input: generated testcase with ~16MB of integer lines, CRLF ended.
+///   run: cargo bench -p lib --bench fileio [--features mmap]
+///
+use lib::io::provider::fileio::FileIoReadProvider;
+use lib::io::IoReader;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use std::{env, fs, process};
+
+const LINES: usize = 400_000;
+const ROUNDS: u32 = 5;
+
+/// previous provider: read all lines, reverse, then pop
+struct VecLinesProvider {
+    lines: Vec<String>,
+}
+
+impl VecLinesProvider {
+    fn open_for_read(file_path: &Path) -> VecLinesProvider {
+        let buffer = BufReader::new(File::open(file_path).unwrap());
+        let mut lines: Vec<String> = buffer
+            .lines()
+            .map(|line| line.unwrap().trim_end().to_string())
+            .collect();
+        lines.reverse();
+        VecLinesProvider { lines }
+    }
+}
+
+impl IoReader for VecLinesProvider {
+    fn read_bytes(&mut self) -> io::Result<Option<Vec<u8>>> {
+        Ok(self.lines.pop().map(String::into_bytes))
+    }
+
+    fn peek_bytes(&mut self) -> io::Result<Option<&[u8]>> {
+        Ok(self.lines.last().map(String::as_bytes))
+    }
+
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        Ok(self.lines.pop())
+    }
+}
+
+fn generate(path: &Path) {
+    let mut out = BufWriter::new(File::create(path).unwrap());
+    let mut seed: u64 = 20030303;
+    for _ in 0..LINES {
+        for col in 0..4 {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let sep = if col == 3 { "\r\n" } else { " " };
+            write!(out, "{}{}", (seed >> 33) as i64 - (1 << 30), sep).unwrap();
+        }
+    }
+}
+
+/// sum of all integers read line by line
+fn consume(mut reader: Box<dyn IoReader>, fast_path: bool) -> i64 {
+    let mut sum = 0i64;
+    loop {
+        let nums = if fast_path {
+            reader.read_i64s().unwrap()
+        } else {
+            reader.read_line().unwrap().map(|line| {
+                line.split_whitespace()
+                    .map(|t| t.parse::<i64>().unwrap())
+                    .collect::<Vec<_>>()
+            })
+        };
+        match nums {
+            Some(nums) => sum = nums.iter().fold(sum, |s, n| s.wrapping_add(*n)),
+            None => return sum,
+        }
+    }
+}
+
+fn bench(name: &str, mut run: impl FnMut() -> i64) -> i64 {
+    let mut best = Duration::MAX;
+    let mut result = 0;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        result = run();
+        best = best.min(start.elapsed());
+    }
+    println!("{:<32} {:>10.2?} (best of {})", name, best, ROUNDS);
+    result
+}
+
+fn main() {
+    let path = env::temp_dir().join(format!("ccc_fileio_bench_{}.tc", process::id()));
+    generate(&path);
+    let size = fs::metadata(&path).unwrap().len();
+    println!(
+        "input: {} lines, {:.1} MB",
+        LINES,
+        size as f64 / (1 << 20) as f64
+    );
+
+    let file_path = path.to_str().unwrap();
+    let expect = bench("vec lines + str parse", || {
+        consume(Box::new(VecLinesProvider::open_for_read(&path)), false)
+    });
+    let results = [
+        bench("stream + str parse", || {
+            consume(FileIoReadProvider::open_for_read(file_path).unwrap(), false)
+        }),
+        bench("stream + read_i64s", || {
+            consume(FileIoReadProvider::open_for_read(file_path).unwrap(), true)
+        }),
+        #[cfg(feature = "mmap")]
+        bench("mmap + read_i64s", || {
+            consume(FileIoReadProvider::open_mapped(file_path).unwrap(), true)
+        }),
+    ];
+    fs::remove_file(&path).unwrap();
+    assert!(results.iter().all(|r| *r == expect), "providers disagree");
+}
@@ -0,0 +1,117 @@
+/// Judge stdin benchmark: StdIoProvider vs FastIoReadProvider.
+///   input: generated testcase with ~16MB of integer lines, redirected to stdin of
+///          a child process of this bench, which times reading only.
+///   run: cargo bench -p lib --bench stdio
+///
+use lib::io::provider::fastio::FastIoReadProvider;
+use lib::io::provider::stdio::StdIoProvider;
+use lib::io::scanner::Scanner;
+use lib::io::IoReader;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use std::{env, fs, process};
+
+const LINES: usize = 400_000;
+const ROUNDS: u32 = 5;
+
+/// env var set in the child process, naming the reader to time
+const CHILD_ENV: &str = "CCC_STDIO_BENCH_READER";
+
+fn generate(path: &Path) {
+    let mut out = BufWriter::new(File::create(path).unwrap());
+    let mut seed: u64 = 20030303;
+    for _ in 0..LINES {
+        for col in 0..4 {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let sep = if col == 3 { "\n" } else { " " };
+            write!(out, "{}{}", (seed >> 33) as i64 - (1 << 30), sep).unwrap();
+        }
+    }
+}
+
+/// sum of all integers in stdin, and time taken
+///   name: "std" or "fast" provider, "+parse" for read_line and str parse,
+///         "+scan" for Scanner::next_i64 tokens.
+fn consume(name: &str) -> (i64, Duration) {
+    let start = Instant::now();
+    let (provider, mode) = name.split_once('+').unwrap_or((name, ""));
+    let mut reader: Box<dyn IoReader> = match provider {
+        "std" => StdIoProvider::new_reader(true).unwrap(),
+        _ => FastIoReadProvider::stdin().unwrap(),
+    };
+    let mut sum = 0i64;
+    if mode == "scan" {
+        let mut sc = Scanner::new(reader);
+        while let Some(n) = sc.next_i64().unwrap() {
+            sum = sum.wrapping_add(n);
+        }
+        return (sum, start.elapsed());
+    }
+    loop {
+        let nums = if mode == "parse" {
+            reader.read_line().unwrap().map(|line| {
+                line.split_whitespace()
+                    .map(|t| t.parse::<i64>().unwrap())
+                    .collect::<Vec<_>>()
+            })
+        } else {
+            reader.read_i64s().unwrap()
+        };
+        match nums {
+            Some(nums) => sum = nums.iter().fold(sum, |s, n| s.wrapping_add(*n)),
+            None => return (sum, start.elapsed()),
+        }
+    }
+}
+
+/// run child reading input from stdin, best time of rounds
+fn bench(title: &str, name: &str, input: &Path) -> i64 {
+    let mut best = Duration::MAX;
+    let mut result = 0;
+    for _ in 0..ROUNDS {
+        let output = Command::new(env::current_exe().unwrap())
+            .env(CHILD_ENV, name)
+            .stdin(Stdio::from(File::open(input).unwrap()))
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{} failed", name);
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let (sum, nanos) = stdout.trim().split_once(' ').unwrap();
+        result = sum.parse().unwrap();
+        best = best.min(Duration::from_nanos(nanos.parse().unwrap()));
+    }
+    println!("{:<32} {:>10.2?} (best of {})", title, best, ROUNDS);
+    result
+}
+
+fn main() {
+    if let Ok(name) = env::var(CHILD_ENV) {
+        let (sum, elapsed) = consume(&name);
+        println!("{} {}", sum, elapsed.as_nanos());
+        return;
+    }
+
+    let path = env::temp_dir().join(format!("ccc_stdio_bench_{}.tc", process::id()));
+    generate(&path);
+    let size = fs::metadata(&path).unwrap().len();
+    println!(
+        "input: {} lines, {:.1} MB",
+        LINES,
+        size as f64 / (1 << 20) as f64
+    );
+
+    let expect = bench("std + str parse", "std+parse", &path);
+    let results = [
+        bench("std + read_i64s", "std", &path),
+        bench("fast + str parse", "fast+parse", &path),
+        bench("fast + read_i64s", "fast", &path),
+        bench("fast + scanner next_i64", "fast+scan", &path),
+    ];
+    fs::remove_file(&path).unwrap();
+    assert!(results.iter().all(|r| *r == expect), "providers disagree");
+}
@@ -19,7 +19,14 @@
 ///   ccc run --all
 ///   ccc run
 ///
+use crate::cli::config::{dotted_key, Config, ConfigError};
+use crate::cli::doc::{CmdDoc, DocRender, TermDoc};
+use crate::cli::suggest::did_you_mean;
+use crate::cli::value::{ArgValue, ValueType};
+use colored::Colorize;
 use debug_ignore::DebugIgnore;
+use std::env;
+use std::fmt::Display;
 
 /// Command and Option's value Specification
 #[derive(Debug, Copy, Clone)]
@@ -31,11 +38,11 @@ pub enum ValueSpec {
 
 impl ValueSpec {
     /// make doc string for help makeing
-    pub fn doc(&self) -> &str {
+    pub fn doc(&self, vtype: &ValueType) -> String {
         match self {
-            ValueSpec::Must => "VALUE",
-            ValueSpec::Maybe => "[VALUE]",
-            ValueSpec::None => "",
+            ValueSpec::Must => vtype.doc(),
+            ValueSpec::Maybe => format!("[{}]", vtype.doc()),
+            ValueSpec::None => "".to_string(),
         }
     }
 }
@@ -43,23 +50,122 @@ impl ValueSpec {
 /// Command Specification
 #[derive(Debug, Copy, Clone)]
 pub struct CmdSpec<'a> {
-    name: &'a str,    // command execute name
-    title: &'a str,   // command short info in one sentence.
-    usage: &'a str,   // command usage sample.
-    desc: &'a str,    // command full description.
-    vspec: ValueSpec, // command has value token or not
+    pub name: &'a str,        // command execute name
+    pub title: &'a str,       // command short info in one sentence.
+    pub usage: &'a str,       // command usage sample.
+    pub desc: &'a str,        // command full description.
+    pub vspec: ValueSpec,     // command has value token or not
+    pub vtype: ValueType<'a>, // command value token's type
+}
+
+impl CmdSpec<'_> {
+    /// spec with all fields empty, base of struct update in command_tree!
+    pub const EMPTY: CmdSpec<'static> = CmdSpec {
+        name: "",
+        title: "",
+        usage: "",
+        desc: "",
+        vspec: ValueSpec::None,
+        vtype: ValueType::Text,
+    };
 }
 
 /// Command Execute Argumens
+/// one node per matched command level, the deepest node owns its parent levels.
 #[derive(Debug, Clone)]
 pub struct CmdExeArgs<'a> {
     cmd: &'a CmdSpec<'a>,
-    opns: Vec<&'a OpnSepc<'a>>,
-    token: &'a str,
+    opns: Vec<OpnArg<'a>>,
+    token: std::option::Option<String>,
+    parent: std::option::Option<Box<CmdExeArgs<'a>>>,
+}
+
+/// Matched Option with its value token
+#[derive(Debug, Clone)]
+pub struct OpnArg<'a> {
+    spec: &'a OpnSepc<'a>,
+    value: std::option::Option<String>,
+    source: ValueSource,
+}
+
+/// Where an option's value came from
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ValueSource {
+    Argv,
+    Env,
+    ProjectConfig,
+    UserConfig,
+    Default,
+}
+
+impl Display for ValueSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValueSource::Argv => write!(f, "argv"),
+            ValueSource::Env => write!(f, "env"),
+            ValueSource::ProjectConfig => write!(f, "project config"),
+            ValueSource::UserConfig => write!(f, "user config"),
+            ValueSource::Default => write!(f, "default"),
+        }
+    }
+}
+
+/// Command line parse error
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CmdError {
+    UnknownFlag(String, std::option::Option<String>), // flag, suggested flag
+    MissingValue(String),
+    UnexpectedValue(String),
+    UnexpectedToken(String, std::option::Option<String>), // token, suggested sub command
+    RepeatedFlag(String),
+    InvalidValue {
+        name: String,
+        value: String,
+        expect: String,
+    },
+}
+
+impl Display for CmdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CmdError::UnknownFlag(flag, suggest) => {
+                write!(f, "unknown flag '{}'", flag)?;
+                CmdError::fmt_suggest(f, suggest)
+            }
+            CmdError::MissingValue(name) => write!(f, "'{}' requires a value", name),
+            CmdError::UnexpectedValue(flag) => write!(f, "flag '{}' takes no value", flag),
+            CmdError::UnexpectedToken(token, suggest) => {
+                write!(f, "unexpected token '{}'", token)?;
+                CmdError::fmt_suggest(f, suggest)
+            }
+            CmdError::RepeatedFlag(flag) => write!(f, "flag '{}' can not be repeated", flag),
+            CmdError::InvalidValue {
+                name,
+                value,
+                expect,
+            } => write!(
+                f,
+                "invalid value '{}' for '{}': expect {}",
+                value, name, expect
+            ),
+        }
+    }
+}
+
+impl CmdError {
+    fn fmt_suggest(
+        f: &mut std::fmt::Formatter<'_>,
+        suggest: &std::option::Option<String>,
+    ) -> std::fmt::Result {
+        match suggest {
+            Some(s) => write!(f, ", did you mean '{}'?", s),
+            None => Ok(()),
+        }
+    }
 }
 
 /// CLI Command's execute function
-type FnCmder = fn(cmd_args: CmdExeArgs) -> Result<i32, String>;
+pub type FnCmder = fn(cmd_args: CmdExeArgs) -> Result<i32, String>;
 
 /// Command Assembly
 #[derive(Debug, Clone)]
@@ -68,15 +174,21 @@ pub struct Command {
     options: Vec<Option>,
     subcmds: Vec<Command>,
     exec_fn: DebugIgnore<FnCmder>,
+    hidden: bool,   // hidden from help and completion
+    config: Config, // config files layer, root command's only
 }
 
 /// Option Sepcification
 #[derive(Debug, Clone, Copy)]
 pub struct OpnSepc<'a> {
-    short: &'a str, // option short flag
-    long: &'a str,  // option long flag
-    title: &'a str,
-    vspec: ValueSpec, // option has value token or not
+    pub short: &'a str, // option short flag
+    pub long: &'a str,  // option long flag
+    pub title: &'a str,
+    pub vspec: ValueSpec,     // option has value token or not
+    pub vtype: ValueType<'a>, // option value token's type
+    pub default: &'a str,     // value if not in argv nor env, "" for none
+    pub env: &'a str,         // environment variable fallback, "" for none
+    pub repeat: bool,         // option may be repeated, values collected into a list
 }
 
 /// Option Assembly
@@ -86,9 +198,194 @@ pub struct Option {
 }
 
 impl Option {
+    /// option specification
+    pub fn spec(&self) -> &OpnSepc<'static> {
+        &self.spec
+    }
+
     /// Try match self Option with CLI arguments
     pub fn try_match(&self, arg: &str) -> bool {
-        self.spec.short == arg || self.spec.long == arg
+        !arg.is_empty() && (self.spec.short == arg || self.spec.long == arg)
+    }
+}
+
+impl OpnSepc<'_> {
+    /// spec with all fields empty, base of struct update in command_tree!
+    pub const EMPTY: OpnSepc<'static> = OpnSepc {
+        short: "",
+        long: "",
+        title: "",
+        vspec: ValueSpec::None,
+        vtype: ValueType::Text,
+        default: "",
+        env: "",
+        repeat: false,
+    };
+
+    /// option's display flag, long flag is preferred. eg. --testcase, -t
+    pub fn flag(&self) -> String {
+        if self.long.is_empty() {
+            format!("-{}", self.short)
+        } else {
+            format!("--{}", self.long)
+        }
+    }
+
+    /// option's CLI flags. eg. ["-t", "--testcase"]
+    pub fn flags(&self) -> Vec<String> {
+        let mut flags = Vec::new();
+        if !self.short.is_empty() {
+            flags.push(format!("-{}", self.short));
+        }
+        if !self.long.is_empty() {
+            flags.push(format!("--{}", self.long));
+        }
+        flags
+    }
+
+    /// Try match self with a flag written as in CLI. eg. -t, --testcase, testcase
+    fn is_flag(&self, flag: &str) -> bool {
+        let name = flag.trim_start_matches('-');
+        !name.is_empty() && (self.short == name || self.long == name)
+    }
+}
+
+impl<'a> OpnArg<'a> {
+    /// matched option specification
+    pub fn spec(&self) -> &'a OpnSepc<'a> {
+        self.spec
+    }
+
+    /// option's value token, if any
+    pub fn value(&self) -> std::option::Option<&str> {
+        self.value.as_deref()
+    }
+
+    /// where the value came from: argv, env or default
+    pub fn source(&self) -> ValueSource {
+        self.source
+    }
+}
+
+impl<'a> CmdExeArgs<'a> {
+    fn new(cmd: &'a CmdSpec<'a>) -> CmdExeArgs<'a> {
+        CmdExeArgs {
+            cmd,
+            opns: Vec::new(),
+            token: None,
+            parent: None,
+        }
+    }
+
+    /// matched command specification
+    pub fn cmd(&self) -> &'a CmdSpec<'a> {
+        self.cmd
+    }
+
+    /// command's value token, if any
+    pub fn token(&self) -> std::option::Option<&str> {
+        self.token.as_deref()
+    }
+
+    /// parent command level, None for root command
+    pub fn parent(&self) -> std::option::Option<&CmdExeArgs<'a>> {
+        self.parent.as_deref()
+    }
+
+    /// options matched on this command level
+    pub fn opns(&self) -> &[OpnArg<'a>] {
+        &self.opns
+    }
+
+    /// Seek option by flag(-t, --testcase) from this level up to root command
+    pub fn opn(&self, flag: &str) -> std::option::Option<&OpnArg<'a>> {
+        match self.opns.iter().rev().find(|o| o.spec.is_flag(flag)) {
+            Some(opn) => Some(opn),
+            None => self.parent().and_then(|p| p.opn(flag)),
+        }
+    }
+
+    /// Whether option flag is present in command line
+    pub fn has_opn(&self, flag: &str) -> bool {
+        self.opn(flag).is_some()
+    }
+
+    /// Option flag's value token
+    pub fn opn_value(&self, flag: &str) -> std::option::Option<&str> {
+        self.opn(flag).and_then(|o| o.value())
+    }
+
+    /// All values of a repeated option flag in command line order
+    pub fn opn_values(&self, flag: &str) -> Vec<&str> {
+        let mut values = match self.parent() {
+            Some(p) => p.opn_values(flag),
+            None => Vec::new(),
+        };
+        values.extend(
+            self.opns
+                .iter()
+                .filter(|o| o.spec.is_flag(flag))
+                .filter_map(|o| o.value()),
+        );
+        values
+    }
+
+    /// Where option flag's value came from, None if option is absent
+    pub fn opn_source(&self, flag: &str) -> std::option::Option<ValueSource> {
+        self.opn(flag).map(|o| o.source())
+    }
+
+    /// All values of a repeated option flag converted to T
+    pub fn get_all<T: ArgValue>(&self, flag: &str) -> Vec<T> {
+        self.opn_values(flag)
+            .into_iter()
+            .filter_map(T::from_arg)
+            .collect()
+    }
+
+    /// Option flag's value converted to T. eg. get::<u32>("--limit")
+    pub fn get<T: ArgValue>(&self, flag: &str) -> std::option::Option<T> {
+        self.opn_value(flag).and_then(T::from_arg)
+    }
+
+    /// command's value token converted to T. eg. get_token::<QuestionId>()
+    pub fn get_token<T: ArgValue>(&self) -> std::option::Option<T> {
+        self.token().and_then(T::from_arg)
+    }
+
+    /// Push option matched in argv, non-repeatable option can be matched once only
+    fn push_argv_opn(
+        &mut self,
+        spec: &'a OpnSepc<'a>,
+        value: std::option::Option<String>,
+    ) -> Result<(), CmdError> {
+        if !spec.repeat && self.opn(&spec.flag()).is_some() {
+            return Err(CmdError::RepeatedFlag(spec.flag()));
+        }
+        self.opns.push(OpnArg {
+            spec,
+            value,
+            source: ValueSource::Argv,
+        });
+        Ok(())
+    }
+
+    /// Command level `up` levels above self, 0 is self
+    fn level_mut(&mut self, up: usize) -> &mut CmdExeArgs<'a> {
+        match up {
+            0 => self,
+            _ => self.parent.as_deref_mut().unwrap().level_mut(up - 1),
+        }
+    }
+
+    /// command names from root to this level. eg. ["ccc", "run"]
+    pub fn cmd_path(&self) -> Vec<&'a str> {
+        let mut path = match self.parent() {
+            Some(p) => p.cmd_path(),
+            None => Vec::new(),
+        };
+        path.push(self.cmd.name);
+        path
     }
 }
 
@@ -100,7 +397,131 @@ impl Command {
             options: Vec::new(),
             subcmds: Vec::new(),
             exec_fn: exec.into(),
+            hidden: false,
+            config: Config::default(),
+        }
+    }
+
+    /// assemble command with its options and sub commands, used by command_tree!
+    ///   panic if names or flags are duplicated, see validate.
+    pub fn assemble(
+        spec: CmdSpec<'static>,
+        exec: FnCmder,
+        options: Vec<OpnSepc<'static>>,
+        subcmds: Vec<Command>,
+    ) -> Command {
+        let mut cmd = Command::new(spec, exec);
+        cmd.options = options.into_iter().map(|spec| Option { spec }).collect();
+        cmd.subcmds = subcmds;
+        if let Err(e) = cmd.validate() {
+            panic!("invalid command tree '{}': {}", spec.name, e);
+        }
+        cmd
+    }
+
+    /// Check duplicated sub command names and option flags in the whole tree.
+    ///   flags are unique along a command path, as options are searched up to root.
+    pub fn validate(&self) -> Result<(), String> {
+        self.validate_level(&mut Vec::new())
+    }
+
+    fn validate_level(&self, flags: &mut Vec<String>) -> Result<(), String> {
+        let depth = flags.len();
+        for opn in &self.options {
+            let spec = &opn.spec;
+            let written = [("-", spec.short), ("--", spec.long)];
+            for flag in written.iter().filter(|(_, n)| !n.is_empty()) {
+                let flag = format!("{}{}", flag.0, flag.1);
+                if flags.contains(&flag) {
+                    return Err(format!(
+                        "duplicated flag '{}' in '{}'",
+                        flag, self.spec.name
+                    ));
+                }
+                flags.push(flag);
+            }
+        }
+        for (i, sub) in self.subcmds.iter().enumerate() {
+            let name = sub.spec.name;
+            if self.subcmds[..i].iter().any(|s| s.spec.name == name) {
+                return Err(format!(
+                    "duplicated sub command '{}' in '{}'",
+                    name, self.spec.name
+                ));
+            }
+            sub.validate_level(flags)?;
+        }
+        flags.truncate(depth);
+        Ok(())
+    }
+
+    /// command specification
+    pub fn spec(&self) -> &CmdSpec<'static> {
+        &self.spec
+    }
+
+    /// registered options
+    pub fn options(&self) -> &[Option] {
+        &self.options
+    }
+
+    /// registered sub commands, hidden ones included
+    pub fn subcmds(&self) -> &[Command] {
+        &self.subcmds
+    }
+
+    /// hide self from help and completion, still can be executed
+    pub fn hide(&mut self) -> &mut Command {
+        self.hidden = true;
+        self
+    }
+
+    /// Whether self is hidden from help and completion
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /// set config files layer to root command, config keys are checked with command tree.
+    pub fn set_config(&mut self, config: Config) -> Result<&mut Command, ConfigError> {
+        config.check(self)?;
+        self.config = config;
+        Ok(self)
+    }
+
+    /// config files layer
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Effective option values without argv, for all commands in tree.
+    ///   (config key, values, source), flag option's value is "true".
+    pub fn effective_config(&self) -> Result<Vec<(String, Vec<String>, ValueSource)>, CmdError> {
+        let mut items = Vec::new();
+        self.collect_config(self, &mut Vec::new(), &mut items)?;
+        Ok(items)
+    }
+
+    fn collect_config<'a>(
+        &self,
+        cmd: &'a Command,
+        path: &mut Vec<&'a str>,
+        items: &mut Vec<(String, Vec<String>, ValueSource)>,
+    ) -> Result<(), CmdError> {
+        for opn in cmd.options.iter().filter(|o| !o.spec.long.is_empty()) {
+            if let Some((values, source)) = self.fallback_values(path, &opn.spec)? {
+                let values = values
+                    .into_iter()
+                    .map(|v| v.unwrap_or_else(|| "true".to_string()))
+                    .collect();
+                items.push((dotted_key(path, opn.spec.long), values, source));
+            }
+        }
+        for sub in &cmd.subcmds {
+            path.push(sub.spec.name);
+            self.collect_config(sub, path, items)?;
+            path.pop();
         }
+        Ok(())
     }
 
     /// Try match self command with CLI arguments
@@ -120,145 +541,424 @@ impl Command {
         self.options.last_mut().unwrap()
     }
 
-    /// make command document
+    /// make command document for terminal help
     pub fn make_doc(&self) -> Vec<String> {
-        let mut doc: Vec<String> = Vec::new();
-
-        // 1) command self document
-        doc.push(format!("{:-^50}", "Brife Info"));
-        doc.push(format!("{:>8} : {}", "Name", self.spec.name));
-        doc.push(format!("{:>8} : {}", "Title", self.spec.title));
-        doc.push(format!("{:>8} : {}", "Usage", self.spec.usage));
-        doc.push(format!("{:>8} : {}", "Value", self.spec.vspec.doc()));
-        if self.spec.desc.len() > 0 {
-            doc.push(format!("{:>8} : {}", "Desc", self.spec.desc));
+        TermDoc::default().render(&CmdDoc::new(self))
+    }
+
+    /// Parse command line and seek exec function
+    ///   args: full argv, args[0] is the program name and is skipped.
+    pub fn parse(&self, args: Vec<String>) -> Result<CmdExeArgs<'_>, CmdError> {
+        self.parse_chain(args, &mut vec![self])
+    }
+
+    /// Parse command line, chain is the matched commands from root, also when parse failed.
+    fn parse_chain<'c>(
+        &'c self,
+        args: Vec<String>,
+        chain: &mut Vec<&'c Command>,
+    ) -> Result<CmdExeArgs<'c>, CmdError> {
+        let mut cmd_args = CmdExeArgs::new(&self.spec);
+        let mut opns_end = false;
+        let mut iter = args.into_iter().skip(1);
+
+        while let Some(arg) = iter.next() {
+            let cmd = *chain.last().unwrap();
+            if !opns_end && arg == "--" {
+                // end of options, all following args are value tokens
+                opns_end = true;
+            } else if !opns_end && arg.starts_with("--") {
+                // long flag: --flag or --flag=value
+                let (name, inline) = match arg[2..].split_once('=') {
+                    Some((name, value)) => (name, Some(value.to_string())),
+                    None => (&arg[2..], None),
+                };
+                let flag = format!("--{}", name);
+                let spec = Command::seek_option(chain, &flag)
+                    .ok_or_else(|| Command::unknown_flag(chain, &flag))?;
+                let value = Command::opn_value(spec, inline, &mut iter)?;
+                cmd_args.push_argv_opn(spec, value)?;
+            } else if !opns_end && arg.len() > 1 && arg.starts_with('-') {
+                // short flags: -t, -tVALUE, clustered -it
+                let cluster = &arg[1..];
+                for (idx, c) in cluster.char_indices() {
+                    let flag = format!("-{}", c);
+                    let spec = Command::seek_option(chain, &flag)
+                        .ok_or_else(|| Command::unknown_flag(chain, &flag))?;
+                    let rest = &cluster[idx + c.len_utf8()..];
+                    if let ValueSpec::None = spec.vspec {
+                        cmd_args.push_argv_opn(spec, None)?;
+                        continue;
+                    }
+                    let inline = (!rest.is_empty()).then(|| rest.to_string());
+                    let value = Command::opn_value(spec, inline, &mut iter)?;
+                    cmd_args.push_argv_opn(spec, value)?;
+                    break;
+                }
+            } else if let Some(subcmd) = cmd.subcmds.iter().find(|c| !opns_end && c.try_match(&arg))
+            {
+                // descend to sub command
+                if let (ValueSpec::Must, None) = (cmd.spec.vspec, &cmd_args.token) {
+                    return Err(CmdError::MissingValue(cmd.spec.name.to_string()));
+                }
+                let mut sub_args = CmdExeArgs::new(&subcmd.spec);
+                sub_args.parent = Some(Box::new(cmd_args));
+                cmd_args = sub_args;
+                chain.push(subcmd);
+            } else if cmd_args.token.is_none() && !matches!(cmd.spec.vspec, ValueSpec::None) {
+                if let Err(e) = Command::check_value(cmd.spec.name, &cmd.spec.vtype, &arg) {
+                    // invalid value may be a mistyped sub command
+                    return match cmd.suggest_subcmd(&arg) {
+                        Some(suggest) => Err(CmdError::UnexpectedToken(arg, Some(suggest))),
+                        None => Err(e),
+                    };
+                }
+                cmd_args.token = Some(arg);
+            } else {
+                let suggest = cmd.suggest_subcmd(&arg);
+                return Err(CmdError::UnexpectedToken(arg, suggest));
+            }
         }
 
-        // 2) options brife document
-        if self.options.len() > 0 {
-            doc.push(format!("{:-^50}", "Options"));
-            for option in &self.options {
-                let flag = |x: &str, p: &str| {
-                    if x.len() == 0 {
-                        "".to_string()
-                    } else {
-                        format!("{}{}", p, x)
+        if let (ValueSpec::Must, None) = (cmd_args.cmd.vspec, &cmd_args.token) {
+            return Err(CmdError::MissingValue(cmd_args.cmd.name.to_string()));
+        }
+
+        // options absent in argv fall back to env var, then config files, then default value
+        let path: Vec<&str> = chain[1..].iter().map(|cmd| cmd.spec.name).collect();
+        for (depth, cmd) in chain.iter().enumerate() {
+            for opn in &cmd.options {
+                if cmd_args.has_opn(&opn.spec.flag()) {
+                    continue;
+                }
+                if let Some((values, source)) = self.fallback_values(&path[..depth], &opn.spec)? {
+                    let level = cmd_args.level_mut(chain.len() - 1 - depth);
+                    for value in values {
+                        level.opns.push(OpnArg {
+                            spec: &opn.spec,
+                            value,
+                            source,
+                        });
                     }
-                };
-                doc.push(format!(
-                    "{:4} {:10} {:6} {}",
-                    flag(option.spec.short, "-"),
-                    flag(option.spec.long, "--"),
-                    option.spec.vspec.doc(),
-                    option.spec.title
-                ));
+                }
             }
         }
+        Ok(cmd_args)
+    }
 
-        // 3) sub commands brife document
-        if self.subcmds.len() > 0 {
-            doc.push(format!("{:-^50}", "Sub Commands"));
-            for subcmd in &self.subcmds {
-                doc.push(format!(
-                    "{:>10} {:6} {}",
-                    subcmd.spec.name,
-                    subcmd.spec.vspec.doc(),
-                    subcmd.spec.title
-                ));
+    /// Resolve option's fallback values from env var, then config files, then default value.
+    ///   path: sub command names from root command to option's owner command.
+    ///   flag option(ValueSpec::None) is present if env var is set, except "", "0" and "false".
+    #[allow(clippy::type_complexity)]
+    fn fallback_values(
+        &self,
+        path: &[&str],
+        spec: &OpnSepc,
+    ) -> Result<std::option::Option<(Vec<std::option::Option<String>>, ValueSource)>, CmdError>
+    {
+        if let Some(value) = (!spec.env.is_empty())
+            .then(|| env::var(spec.env).ok())
+            .flatten()
+        {
+            if let ValueSpec::None = spec.vspec {
+                let is_set = !matches!(value.as_str(), "" | "0" | "false");
+                return Ok(is_set.then_some((vec![None], ValueSource::Env)));
             }
+            Command::check_value(spec.env, &spec.vtype, &value)?;
+            return Ok(Some((vec![Some(value)], ValueSource::Env)));
+        }
+        if let Some(found) = self.config.values(path, spec) {
+            return Ok(Some(found));
+        }
+        match spec.vspec {
+            ValueSpec::None => Ok(None),
+            _ if spec.default.is_empty() => Ok(None),
+            _ => Ok(Some((
+                vec![Some(spec.default.to_string())],
+                ValueSource::Default,
+            ))),
         }
+    }
 
-        dbg!(doc.clone());
-        doc
+    /// Parse command line, then call the deepest matched command's exec function.
+    ///   parse error is returned as Err with formatted message.
+    pub fn run(&self, args: Vec<String>) -> Result<i32, String> {
+        let cmd_args = self.parse(args).map_err(|e| e.to_string())?;
+        let cmd = self.seek_subcmd(&cmd_args.cmd_path()[1..]);
+        (cmd.exec_fn)(cmd_args)
     }
 
-    /// Parse command line and seek exec function
-    pub fn parse(&self, args: Vec<String>) -> CmdExeArgs {
-        todo!();
-        // let mut cmd_args: CmdExeArgs;
-        // cmd_args.cmd = &self.spec;
-        // for arg in args {
-        //     if arg.starts_with("-") {
-        //         // try match options
-        //         let token = arg.trim_start_matches("-");
-        //         for opn in self.options {
-        //             if opn.try_match(token) {
-        //                 cmd_args.opns.push(&opn.spec);
-        //                 continue;
-        //             }
-        //         }
-        //     } else {
-        //     }
-        // }
-        // cmd_args
+    /// Execute command line as a process, returns process exit code.
+    ///   0..: exec function's result
+    ///   1  : exec function failed
+    ///   2  : command line parse failed
+    ///   -h/--help prints help of the deepest matched command and returns 0, even if parse failed.
+    pub fn exec(&self, args: Vec<String>) -> i32 {
+        let mut chain = vec![self];
+        let parsed = self.parse_chain(args.clone(), &mut chain);
+        let cmd = *chain.last().unwrap();
+        if Command::help_requested(&chain, &args) {
+            for line in cmd.make_doc() {
+                println!("{}", line);
+            }
+            return 0;
+        }
+        let cmd_args = match parsed {
+            Ok(cmd_args) => cmd_args,
+            Err(e) => {
+                eprintln!("{} {}", "error:".red().bold(), e);
+                eprintln!("{:>6} {}", "usage:", cmd.spec.usage);
+                return 2;
+            }
+        };
+        match (cmd.exec_fn)(cmd_args) {
+            Ok(code) => code,
+            Err(e) => {
+                eprintln!("{} {}", "error:".red().bold(), e);
+                1
+            }
+        }
+    }
+
+    /// Whether -h/--help is in args before "--", and is an option of the chain
+    fn help_requested(chain: &[&Command], args: &[String]) -> bool {
+        let Some(spec) = Command::seek_option(chain, "--help") else {
+            return false;
+        };
+        let short = format!("-{}", spec.short);
+        args.iter()
+            .skip(1)
+            .take_while(|arg| *arg != "--")
+            .any(|arg| *arg == "--help" || (!spec.short.is_empty() && *arg == short))
+    }
+
+    /// Seek sub command by names path under self. eg. ["run"]
+    ///   the deepest matched command is returned, self if none matched.
+    pub fn seek_subcmd(&self, path: &[&str]) -> &Command {
+        match path.split_first() {
+            Some((name, rest)) => self
+                .subcmds
+                .iter()
+                .find(|c| c.try_match(name))
+                .map_or(self, |c| c.seek_subcmd(rest)),
+            None => self,
+        }
+    }
+
+    /// Suggest the closest visible sub command name for a mistyped token
+    fn suggest_subcmd(&self, token: &str) -> std::option::Option<String> {
+        let visible = self.subcmds.iter().filter(|c| !c.is_hidden());
+        did_you_mean(token, visible.map(|c| c.spec.name)).map(String::from)
+    }
+
+    /// Unknown flag error with the closest short or long flag in matched commands
+    fn unknown_flag(chain: &[&Command], flag: &str) -> CmdError {
+        let opns = || chain.iter().flat_map(|cmd| cmd.options.iter());
+        let names = opns().flat_map(|opn| [opn.spec.short, opn.spec.long]);
+        let suggest = did_you_mean(flag.trim_start_matches('-'), names).map(|name| {
+            let opn = opns().find(|opn| opn.try_match(name)).unwrap();
+            if opn.spec.short == name {
+                format!("-{}", name)
+            } else {
+                format!("--{}", name)
+            }
+        });
+        CmdError::UnknownFlag(flag.to_string(), suggest)
+    }
+
+    /// Seek option by name from the deepest matched command up to root command
+    ///   flag: --long matches long flag only, -s matches short flag only.
+    fn seek_option<'a>(
+        chain: &[&'a Command],
+        flag: &str,
+    ) -> std::option::Option<&'a OpnSepc<'static>> {
+        let matched = |spec: &OpnSepc| match flag.strip_prefix("--") {
+            Some(long) => spec.long == long,
+            None => spec.short == &flag[1..],
+        };
+        chain
+            .iter()
+            .rev()
+            .flat_map(|cmd| cmd.options.iter())
+            .find(|opn| matched(&opn.spec))
+            .map(|opn| &opn.spec)
+    }
+
+    /// Resolve option's value by ValueSpec.
+    ///   Must: inline value or next argument.
+    ///   Maybe: inline value only, eg. --flag=value or -fvalue.
+    ///   None: no value allowed.
+    fn opn_value(
+        spec: &OpnSepc,
+        inline: std::option::Option<String>,
+        args: &mut impl Iterator<Item = String>,
+    ) -> Result<std::option::Option<String>, CmdError> {
+        let value = match (spec.vspec, inline) {
+            (ValueSpec::None, Some(_)) => return Err(CmdError::UnexpectedValue(spec.flag())),
+            (ValueSpec::None, None) => None,
+            (ValueSpec::Maybe, inline) => inline,
+            (ValueSpec::Must, Some(value)) => Some(value),
+            (ValueSpec::Must, None) => Some(
+                args.next()
+                    .ok_or_else(|| CmdError::MissingValue(spec.flag()))?,
+            ),
+        };
+        if let Some(value) = &value {
+            Command::check_value(&spec.flag(), &spec.vtype, value)?;
+        }
+        Ok(value)
+    }
+
+    /// Validate value token with its declared ValueType
+    fn check_value(name: &str, vtype: &ValueType, value: &str) -> Result<(), CmdError> {
+        if vtype.check(value) {
+            Ok(())
+        } else {
+            Err(CmdError::InvalidValue {
+                name: name.to_string(),
+                value: value.to_string(),
+                expect: vtype.expect(),
+            })
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::cli::complete::{complete_script, Shell, SHELLS};
+    use crate::cli::doc::{ManDoc, MarkdownDoc};
+    use crate::cli::value::QuestionId;
+    use std::path::PathBuf;
+    use std::time::Duration;
 
-    fn default_cmder<'a>(cmd_args: CmdExeArgs) -> Result<i32, String> {
-        dbg!(cmd_args);
+    fn default_cmder(_cmd_args: CmdExeArgs) -> Result<i32, String> {
         Ok(0)
     }
 
+    fn run_cmder(cmd_args: CmdExeArgs) -> Result<i32, String> {
+        match cmd_args.token() {
+            Some("03s3") => Ok(cmd_args.opn_values("-t").len() as i32),
+            Some(q) => Err(format!("question '{}' not found", q)),
+            None => Ok(100),
+        }
+    }
+
     fn build_commands() -> Command {
-        let mut rtcmd = Command::new(
-            CmdSpec {
-                name: "ccc",
+        crate::command_tree!(
+            "ccc" => default_cmder, {
                 title: "canada computer compenont",
                 usage: "ccc -options sub-command",
                 vspec: ValueSpec::Maybe,
-                desc: "",
             },
-            default_cmder,
-        );
-        rtcmd.reg_option(OpnSepc {
-            short: "h",
-            long: "help",
-            title: "help information about application",
-            vspec: ValueSpec::None,
-        });
-        rtcmd.reg_option(OpnSepc {
-            short: "",
-            long: "dir",
-            title: "assign testcase base direction",
-            vspec: ValueSpec::Must,
-        });
-        let runcmd = rtcmd.reg_subcmd(
-            CmdSpec {
-                name: "run",
-                title: "run ccc question solvment",
-                usage: "run 03s3 -t tc1",
-                desc: "",
-                vspec: ValueSpec::Maybe,
-            },
-            default_cmder,
+            options: [
+                { short: "h", long: "help", title: "help information about application" },
+                {
+                    long: "dir",
+                    title: "assign testcase base direction",
+                    vspec: ValueSpec::Must,
+                    vtype: ValueType::Path,
+                    env: "CCC_CMD_TEST_DIR",
+                },
+            ],
+            subcmds: [
+                (
+                    "run" => run_cmder, {
+                        title: "run ccc question solvment",
+                        usage: "run 03s3 -t tc1",
+                        vspec: ValueSpec::Maybe,
+                        vtype: ValueType::QuestionId,
+                    },
+                    options: [
+                        {
+                            short: "t",
+                            long: "testcase",
+                            title: "testcase file name",
+                            vspec: ValueSpec::Must,
+                            vtype: ValueType::Testcase,
+                            repeat: true,
+                        },
+                        {
+                            short: "l",
+                            long: "limit",
+                            title: "time limit of each testcase",
+                            vspec: ValueSpec::Must,
+                            vtype: ValueType::Duration,
+                            default: "1s",
+                        },
+                    ],
+                    subcmds: [(
+                        "all" => default_cmder, {
+                            title: "run all ccc questions",
+                            usage: "ccc run all",
+                        },
+                    )],
+                ),
+                (
+                    "search" => default_cmder, {
+                        title: "Search ccc question",
+                        usage: "ccc search 03s3",
+                        vspec: ValueSpec::Must,
+                    },
+                    options: [{
+                        short: "i",
+                        long: "letter-case",
+                        title: "Search by letter case sentitive",
+                    }],
+                ),
+                (
+                    "completions" => default_cmder, {
+                        title: "make shell completion script",
+                        usage: "ccc completions bash",
+                        vspec: ValueSpec::Must,
+                        vtype: ValueType::Choice(&SHELLS),
+                    },
+                    hidden: true,
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicated flag '-t' in 'all'")]
+    fn cmd_tree_duplicated_flag() {
+        crate::command_tree!(
+            "ccc" => default_cmder, {},
+            subcmds: [(
+                "run" => default_cmder, {},
+                options: [{ short: "t", long: "testcase" }],
+                subcmds: [(
+                    "all" => default_cmder, {},
+                    options: [{ short: "t", long: "time" }],
+                )],
+            )],
         );
-        runcmd.reg_option(OpnSepc {
-            short: "t",
-            long: "testcase",
-            title: "testcase file name",
-            vspec: ValueSpec::Must,
-        });
-        let searchcmd = rtcmd.reg_subcmd(
+    }
+
+    #[test]
+    fn cmd_tree_validate() {
+        let mut rtcmd = build_commands();
+        assert_eq!(rtcmd.validate(), Ok(()));
+        rtcmd.reg_subcmd(
             CmdSpec {
                 name: "search",
-                title: "Search ccc question",
-                usage: "ccc search 03s3",
-                desc: "",
-                vspec: ValueSpec::Must,
+                ..CmdSpec::EMPTY
             },
             default_cmder,
         );
-        searchcmd.reg_option(OpnSepc {
-            short: "i",
-            long: "letter-case",
-            title: "Search by letter case sentitive",
-            vspec: ValueSpec::None,
-        });
-        rtcmd
+        assert_eq!(
+            rtcmd.validate(),
+            Err("duplicated sub command 'search' in 'ccc'".to_string())
+        );
+        // same flag in sibling commands is fine
+        let rtcmd = crate::command_tree!(
+            "ccc" => default_cmder, {},
+            subcmds: [
+                ("run" => default_cmder, {}, options: [{ short: "t" }]),
+                ("search" => default_cmder, {}, options: [{ short: "t" }]),
+            ],
+        );
+        assert_eq!(rtcmd.subcmds().len(), 2);
     }
 
     #[test]
@@ -277,10 +977,10 @@ mod test {
         );
         assert_eq!(
             doc[7],
-            "     --dir      VALUE  assign testcase base direction"
+            "     --dir      PATH   assign testcase base direction [env: CCC_CMD_TEST_DIR]"
         );
         assert_eq!(doc[8], "-------------------Sub Commands-------------------");
-        assert_eq!(doc[9], "       run [VALUE] run ccc question solvment");
+        assert_eq!(doc[9], "       run [QID]  run ccc question solvment");
         assert_eq!(doc[10], "    search VALUE  Search ccc question");
 
         let doc = rtcmd.subcmds[0].make_doc();
@@ -288,15 +988,320 @@ mod test {
         assert_eq!(doc[1], "    Name : run");
         assert_eq!(doc[2], "   Title : run ccc question solvment");
         assert_eq!(doc[3], "   Usage : run 03s3 -t tc1");
-        assert_eq!(doc[4], "   Value : [VALUE]");
+        assert_eq!(doc[4], "   Value : [QID]");
         assert_eq!(doc[5], "---------------------Options----------------------");
-        assert_eq!(doc[6], "-t   --testcase VALUE  testcase file name");
+        assert_eq!(
+            doc[6],
+            "-t   --testcase TC     testcase file name [repeatable]"
+        );
+        assert_eq!(
+            doc[7],
+            "-l   --limit    TIME   time limit of each testcase [default: 1s]"
+        );
+    }
+
+    fn argv(line: &[&str]) -> Vec<String> {
+        line.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn cmd_parse() {
+        let rtcmd = build_commands();
+        let args = rtcmd
+            .parse(argv(&["ccc", "run", "03s3", "-t", "tc1"]))
+            .unwrap();
+        assert_eq!(args.cmd().name, "run");
+        assert_eq!(args.cmd_path(), vec!["ccc", "run"]);
+        assert_eq!(args.token(), Some("03s3"));
+        assert_eq!(args.opns().len(), 2);
+        assert_eq!(args.opn_value("-t"), Some("tc1"));
+        assert_eq!(args.opn_value("--testcase"), Some("tc1"));
+        assert_eq!(args.parent().unwrap().token(), None);
+
+        let args = rtcmd
+            .parse(argv(&["ccc", "search", "-i", "floor plan"]))
+            .unwrap();
+        assert_eq!(args.cmd_path(), vec!["ccc", "search"]);
+        assert_eq!(args.token(), Some("floor plan"));
+        assert!(args.has_opn("--letter-case"));
+        assert_eq!(args.opn_value("-i"), None);
+
+        let args = rtcmd.parse(argv(&["ccc"])).unwrap();
+        assert_eq!(args.cmd().name, "ccc");
+        assert_eq!(args.token(), None);
+    }
+
+    #[test]
+    fn cmd_parse_flag_forms() {
+        let rtcmd = build_commands();
+
+        // --flag=value, parent option after sub command
+        let args = rtcmd
+            .parse(argv(&["ccc", "run", "--testcase=tc2", "--dir", "src"]))
+            .unwrap();
+        assert_eq!(args.opn_value("--testcase"), Some("tc2"));
+        assert_eq!(args.opn_value("--dir"), Some("src"));
+        assert!(args.parent().unwrap().opns().is_empty());
+
+        // -tVALUE and clustered short flags
+        let args = rtcmd.parse(argv(&["ccc", "run", "-ttc3"])).unwrap();
+        assert_eq!(args.opn_value("-t"), Some("tc3"));
+        let args = rtcmd.parse(argv(&["ccc", "search", "-ih", "x"])).unwrap();
+        assert!(args.has_opn("-i"));
+        assert!(args.has_opn("--help"));
+
+        // -- end of options
+        let args = rtcmd.parse(argv(&["ccc", "search", "--", "-i"])).unwrap();
+        assert!(!args.has_opn("-i"));
+        assert_eq!(args.token(), Some("-i"));
+        let args = rtcmd.parse(argv(&["ccc", "--", "run"])).unwrap();
+        assert_eq!(args.cmd().name, "ccc");
+        assert_eq!(args.token(), Some("run"));
+    }
+
+    #[test]
+    fn cmd_parse_errors() {
+        let rtcmd = build_commands();
+        let parse = |line: &[&str]| rtcmd.parse(argv(line)).unwrap_err();
+
+        assert_eq!(
+            parse(&["ccc", "run", "--testcsae"]),
+            CmdError::UnknownFlag("--testcsae".to_string(), Some("--testcase".to_string()))
+        );
+        assert_eq!(
+            parse(&["ccc", "run", "-x"]),
+            CmdError::UnknownFlag("-x".to_string(), None)
+        );
+        assert_eq!(
+            parse(&["ccc", "run", "-t"]),
+            CmdError::MissingValue("--testcase".to_string())
+        );
+        assert_eq!(
+            parse(&["ccc", "search"]),
+            CmdError::MissingValue("search".to_string())
+        );
+        assert_eq!(
+            parse(&["ccc", "--help=yes"]),
+            CmdError::UnexpectedValue("--help".to_string())
+        );
+        assert_eq!(
+            parse(&["ccc", "run", "03s3", "04s1"]),
+            CmdError::UnexpectedToken("04s1".to_string(), None)
+        );
+        assert_eq!(
+            parse(&["ccc", "run", "-t"]).to_string(),
+            "'--testcase' requires a value"
+        );
+    }
+
+    #[test]
+    fn cmd_parse_typed_values() {
+        let rtcmd = build_commands();
+        let args = rtcmd
+            .parse(argv(&["ccc", "run", "03s3", "--limit", "2s", "--dir=src"]))
+            .unwrap();
+        assert_eq!(args.get::<Duration>("-l"), Some(Duration::from_secs(2)));
+        assert_eq!(args.get::<PathBuf>("--dir"), Some(PathBuf::from("src")));
+        assert_eq!(args.get::<String>("-t"), None);
+        assert_eq!(args.get_token::<QuestionId>().unwrap().to_string(), "03s3");
+
+        assert_eq!(
+            rtcmd
+                .parse(argv(&["ccc", "run", "03s3", "-l", "2days"]))
+                .unwrap_err()
+                .to_string(),
+            "invalid value '2days' for '--limit': expect duration like 2s, 500ms"
+        );
+        assert_eq!(
+            rtcmd.parse(argv(&["ccc", "run", "floor"])).unwrap_err(),
+            CmdError::InvalidValue {
+                name: "run".to_string(),
+                value: "floor".to_string(),
+                expect: "question id like 03s3".to_string(),
+            }
+        );
+        assert!(rtcmd
+            .parse(argv(&["ccc", "--dir", "./not/exist/dir"]))
+            .is_err());
+    }
+
+    #[test]
+    fn cmd_parse_fallback_values() {
+        let rtcmd = build_commands();
+        let args = rtcmd
+            .parse(argv(&[
+                "ccc",
+                "run",
+                "-t",
+                "tc1",
+                "-t",
+                "tc2",
+                "--testcase=tc3",
+            ]))
+            .unwrap();
+        assert_eq!(args.opn_values("-t"), vec!["tc1", "tc2", "tc3"]);
+        assert_eq!(args.get_all::<String>("--testcase").len(), 3);
+        assert_eq!(args.opn_source("-t"), Some(ValueSource::Argv));
+        assert_eq!(
+            args.get::<Duration>("--limit"),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(args.opn_source("--limit"), Some(ValueSource::Default));
+        assert_eq!(
+            rtcmd
+                .parse(argv(&["ccc", "run", "-l", "1s", "-l", "2s"]))
+                .unwrap_err(),
+            CmdError::RepeatedFlag("--limit".to_string())
+        );
+
+        env::set_var("CCC_CMD_TEST_DIR", "src");
+        let args = rtcmd.parse(argv(&["ccc", "run"])).unwrap();
+        assert_eq!(args.opn_value("--dir"), Some("src"));
+        assert_eq!(args.opn_source("--dir"), Some(ValueSource::Env));
+        assert_eq!(args.parent().unwrap().opns().len(), 1);
+        let args = rtcmd.parse(argv(&["ccc", "--dir", "."])).unwrap();
+        assert_eq!(args.opn_value("--dir"), Some("."));
+        assert_eq!(args.opn_source("--dir"), Some(ValueSource::Argv));
+        env::remove_var("CCC_CMD_TEST_DIR");
+    }
+
+    #[test]
+    fn cmd_parse_suggestions() {
+        let rtcmd = build_commands();
+        let parse = |line: &[&str]| rtcmd.parse(argv(line)).unwrap_err();
+
+        assert_eq!(
+            parse(&["ccc", "run", "alll"]),
+            CmdError::UnexpectedToken("alll".to_string(), Some("all".to_string()))
+        );
+        assert_eq!(
+            parse(&["ccc", "run", "all", "03s3"]),
+            CmdError::UnexpectedToken("03s3".to_string(), None)
+        );
+        assert_eq!(
+            parse(&["ccc", "run", "--tc"]),
+            CmdError::UnknownFlag("--tc".to_string(), None)
+        );
+        assert_eq!(
+            parse(&["ccc", "run", "--t"]),
+            CmdError::UnknownFlag("--t".to_string(), Some("-t".to_string()))
+        );
+        assert_eq!(
+            parse(&["ccc", "run", "-T", "tc1"]),
+            CmdError::UnknownFlag("-T".to_string(), Some("-t".to_string()))
+        );
+        assert_eq!(
+            parse(&["ccc", "run", "--dri", "src"]).to_string(),
+            "unknown flag '--dri', did you mean '--dir'?"
+        );
+        assert_eq!(
+            parse(&["ccc", "search", "x", "--leter-case"]).to_string(),
+            "unknown flag '--leter-case', did you mean '--letter-case'?"
+        );
+        // hidden sub commands are never suggested
+        assert_eq!(
+            parse(&["ccc", "x", "completion"]),
+            CmdError::UnexpectedToken("completion".to_string(), None)
+        );
+        assert_eq!(
+            parse(&["ccc", "x", "serch"]),
+            CmdError::UnexpectedToken("serch".to_string(), Some("search".to_string()))
+        );
+    }
+
+    #[test]
+    fn cmd_doc_renders() {
+        let rtcmd = build_commands();
+        let cmd_doc = CmdDoc::new(&rtcmd);
+
+        // terminal help: sub command tree, wrapped to width
+        let doc = TermDoc::new(40).render(&cmd_doc);
+        let tree = doc.iter().position(|l| l.contains("Command Tree")).unwrap();
+        assert_eq!(
+            doc[tree + 1..],
+            [
+                "run [QID] : run ccc question solvment",
+                "    usage: run 03s3 -t tc1",
+                "  all : run all ccc questions",
+                "      usage: ccc run all",
+                "search VALUE : Search ccc question",
+                "    usage: ccc search 03s3",
+            ]
+        );
+        let doc = TermDoc::new(40).render(&cmd_doc.subcmds[0]);
+        assert_eq!(
+            doc[6],
+            "-t   --testcase TC     testcase file name [repeatable]"
+        );
+
+        // man pages: one per visible command
+        let pages = ManDoc::pages(&cmd_doc);
+        let names: Vec<&str> = pages.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(
+            names,
+            ["ccc.1", "ccc-run.1", "ccc-run-all.1", "ccc-search.1"]
+        );
+        let run_page = &pages[1].1;
+        assert_eq!(run_page[0], ".TH CCC\\-RUN 1");
+        assert_eq!(run_page[2], "ccc\\-run \\- run ccc question solvment");
+        assert!(run_page.contains(&"\\fB\\-t\\fR, \\fB\\-\\-testcase\\fR \\fITC\\fR".to_string()));
+        assert_eq!(run_page.last().unwrap(), "\\fBccc\\fR(1)");
+
+        // markdown reference: whole tree in one document
+        let md = MarkdownDoc.render(&cmd_doc);
+        assert_eq!(md[0], "# ccc");
+        assert!(md.contains(&"## ccc run".to_string()));
+        assert!(md.contains(&"### ccc run all".to_string()));
+        assert!(
+            md.contains(&"| [`run`](#ccc-run) | `[QID]` | run ccc question solvment |".to_string())
+        );
+        assert!(md.contains(
+            &"| `-l`, `--limit` | `TIME` | time limit of each testcase [default: 1s] |".to_string()
+        ));
+        assert!(!md.iter().any(|l| l.contains("completions")));
+    }
+
+    #[test]
+    fn cmd_completion_scripts() {
+        let rtcmd = build_commands();
+        assert_eq!(
+            complete_script(&rtcmd, Shell::Bash, "ccc"),
+            include_str!("snapshots/ccc.bash")
+        );
+        assert_eq!(
+            complete_script(&rtcmd, Shell::Zsh, "ccc"),
+            include_str!("snapshots/ccc.zsh")
+        );
+        assert_eq!(
+            complete_script(&rtcmd, Shell::Fish, "ccc"),
+            include_str!("snapshots/ccc.fish")
+        );
+        let args = rtcmd.parse(argv(&["ccc", "completions", "zsh"])).unwrap();
+        assert_eq!(args.get_token::<Shell>(), Some(Shell::Zsh));
     }
 
     #[test]
     fn cmd_exec() {
-        // let rtcmd = build_commands();
-        // let r = rtcmd.exec(Vec::new(), "".to_string());
-        // assert!(r.is_err());
+        let rtcmd = build_commands();
+        assert_eq!(rtcmd.run(Vec::new()), Ok(0));
+        assert_eq!(rtcmd.run(argv(&["ccc", "run"])), Ok(100));
+        assert_eq!(rtcmd.run(argv(&["ccc", "run", "03s3", "-t", "tc1"])), Ok(1));
+        assert_eq!(
+            rtcmd.run(argv(&["ccc", "run", "04s1"])),
+            Err("question '04s1' not found".to_string())
+        );
+        assert_eq!(
+            rtcmd.run(argv(&["ccc", "search"])),
+            Err("'search' requires a value".to_string())
+        );
+
+        assert_eq!(rtcmd.exec(argv(&["ccc", "run", "03s3"])), 0);
+        assert_eq!(rtcmd.exec(argv(&["ccc", "run", "04s1"])), 1);
+        assert_eq!(rtcmd.exec(argv(&["ccc", "--unknown"])), 2);
+        // help is shown instead of running, also when the rest fails to parse
+        assert_eq!(rtcmd.exec(argv(&["ccc", "run", "04s1", "-h"])), 0);
+        assert_eq!(rtcmd.exec(argv(&["ccc", "search", "--help"])), 0);
+        assert_eq!(rtcmd.exec(argv(&["ccc", "run", "-l", "bad", "-h"])), 0);
+        assert_eq!(rtcmd.exec(argv(&["ccc", "run", "--", "-h"])), 2);
     }
 }
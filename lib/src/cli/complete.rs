@@ -0,0 +1,334 @@
+/// Shell completion script generation from Command tree
+/// Defines:
+///   Shell  : bash, zsh or fish.
+///   complete_script: walk the Command tree, complete sub commands, flags and values.
+/// Dynamic values(question ids, testcase names) are completed by calling back:
+///   <bin> completions --values qid
+///   <bin> completions --values tc
+///
+use crate::cli::cmd::{Command, OpnSepc, ValueSpec};
+use crate::cli::value::{ArgValue, ValueType};
+
+/// Supported shells
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// shell names for Choice value type
+pub const SHELLS: [&str; 3] = ["bash", "zsh", "fish"];
+
+impl ArgValue for Shell {
+    fn from_arg(value: &str) -> Option<Self> {
+        match value {
+            "bash" => Some(Shell::Bash),
+            "zsh" => Some(Shell::Zsh),
+            "fish" => Some(Shell::Fish),
+            _ => None,
+        }
+    }
+}
+
+/// Dynamic value kind, passed back to `<bin> completions --values KIND`
+pub fn value_kind(vtype: &ValueType) -> Option<&'static str> {
+    match vtype {
+        ValueType::QuestionId => Some("qid"),
+        ValueType::Testcase => Some("tc"),
+        _ => None,
+    }
+}
+
+/// Candidates of a value token
+#[derive(Clone, Default)]
+struct Candidates {
+    words: Vec<String>,            // static words
+    dynamic: Option<&'static str>, // dynamic value kind
+    files: bool,                   // complete file names
+}
+
+impl Candidates {
+    fn of(vtype: &ValueType) -> Candidates {
+        Candidates {
+            words: match vtype {
+                ValueType::Choice(choices) => choices.iter().map(|c| c.to_string()).collect(),
+                _ => Vec::new(),
+            },
+            dynamic: value_kind(vtype),
+            files: matches!(vtype, ValueType::Path),
+        }
+    }
+}
+
+/// Flattened completion node for one command level
+struct Node<'a> {
+    key: String,                      // command path joined by '_'. eg. ccc_run
+    opns: Vec<&'a OpnSepc<'static>>,  // options of this level and parent levels
+    subcmds: Vec<(&'a str, &'a str)>, // visible sub command names and titles
+    value: Option<Candidates>,        // command's value candidates
+}
+
+fn collect_nodes<'a>(
+    cmd: &'a Command,
+    key: String,
+    parent_opns: &[&'a OpnSepc<'static>],
+) -> Vec<Node<'a>> {
+    let mut opns: Vec<&OpnSepc<'static>> = cmd.options().iter().map(|o| o.spec()).collect();
+    opns.extend(parent_opns);
+    let subcmds: Vec<&Command> = cmd.subcmds().iter().filter(|c| !c.is_hidden()).collect();
+    let value = match cmd.spec().vspec {
+        ValueSpec::None => None,
+        _ => Some(Candidates::of(&cmd.spec().vtype)),
+    };
+    let mut nodes = vec![Node {
+        key: key.clone(),
+        opns: opns.clone(),
+        subcmds: subcmds
+            .iter()
+            .map(|c| (c.spec().name, c.spec().title))
+            .collect(),
+        value,
+    }];
+    for subcmd in subcmds {
+        nodes.extend(collect_nodes(
+            subcmd,
+            format!("{}_{}", key, subcmd.spec().name),
+            &opns,
+        ));
+    }
+    nodes
+}
+
+/// option takes its value from the next word
+fn takes_value(spec: &OpnSepc) -> bool {
+    matches!(spec.vspec, ValueSpec::Must)
+}
+
+/// Make completion script of root command for shell.
+///   bin: executable name the script completes, also used for dynamic value callbacks.
+pub fn complete_script(rtcmd: &Command, shell: Shell, bin: &str) -> String {
+    let nodes = collect_nodes(rtcmd, rtcmd.spec().name.to_string(), &[]);
+    let func: String = bin
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let lines = match shell {
+        Shell::Bash => bash_script(&nodes, &func, bin),
+        Shell::Zsh => zsh_script(&nodes, &func, bin),
+        Shell::Fish => fish_script(&nodes, &func, bin),
+    };
+    lines.join("\n") + "\n"
+}
+
+/// sub command transitions: (key:name, sub key)
+fn transitions(nodes: &[Node]) -> Vec<(String, String)> {
+    nodes
+        .iter()
+        .flat_map(|n| {
+            n.subcmds
+                .iter()
+                .map(move |(s, _)| (format!("{}:{}", n.key, s), format!("{}_{}", n.key, s)))
+        })
+        .collect()
+}
+
+fn bash_words(words: &[String], dynamic: Option<&str>, bin: &str) -> String {
+    let mut words = words.join(" ");
+    if let Some(kind) = dynamic {
+        words = format!("{} $({} completions --values {})", words, bin, kind);
+    }
+    words.trim().to_string()
+}
+
+fn bash_reply(cand: &Candidates, extra: &[String], bin: &str) -> String {
+    let mut words = extra.to_vec();
+    words.extend(cand.words.iter().cloned());
+    let mut parts = Vec::new();
+    let words = bash_words(&words, cand.dynamic, bin);
+    if !words.is_empty() {
+        parts.push(format!("$(compgen -W \"{}\" -- \"${{cur}}\")", words));
+    }
+    if cand.files {
+        parts.push("$(compgen -f -- \"${cur}\")".to_string());
+    }
+    format!("COMPREPLY=({})", parts.join(" "))
+}
+
+fn bash_script(nodes: &[Node], func: &str, bin: &str) -> Vec<String> {
+    let mut lines = vec![
+        format!("# bash completion for {}", bin),
+        format!("_{}() {{", func),
+        "    local cur=\"${COMP_WORDS[COMP_CWORD]}\"".to_string(),
+        "    local prev=\"${COMP_WORDS[COMP_CWORD-1]}\"".to_string(),
+        format!("    local cmd=\"{}\" i", nodes[0].key),
+        "    for ((i = 1; i < COMP_CWORD; i++)); do".to_string(),
+        "        case \"${cmd}:${COMP_WORDS[i]}\" in".to_string(),
+    ];
+    for (from, to) in transitions(nodes) {
+        lines.push(format!("            {}) cmd=\"{}\" ;;", from, to));
+    }
+    lines.push("        esac".to_string());
+    lines.push("    done".to_string());
+    lines.push("    case \"${cmd}\" in".to_string());
+    for node in nodes {
+        lines.push(format!("        {})", node.key));
+        let valued: Vec<_> = node.opns.iter().filter(|o| takes_value(o)).collect();
+        if !valued.is_empty() {
+            lines.push("            case \"${prev}\" in".to_string());
+            for opn in valued {
+                lines.push(format!("                {})", opn.flags().join("|")));
+                let reply = bash_reply(&Candidates::of(&opn.vtype), &[], bin);
+                lines.push(format!("                    {}", reply));
+                lines.push("                    return ;;".to_string());
+            }
+            lines.push("            esac".to_string());
+        }
+        let mut words: Vec<String> = node.opns.iter().flat_map(|o| o.flags()).collect();
+        words.extend(node.subcmds.iter().map(|(s, _)| s.to_string()));
+        let cand = node.value.clone().unwrap_or_default();
+        lines.push(format!("            {}", bash_reply(&cand, &words, bin)));
+        lines.push("            ;;".to_string());
+    }
+    lines.push("    esac".to_string());
+    lines.push("}".to_string());
+    lines.push(format!("complete -F _{} {}", func, bin));
+    lines
+}
+
+fn zsh_compadd(cand: &Candidates, extra: &[String], bin: &str, indent: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut words = extra.to_vec();
+    words.extend(cand.words.iter().cloned());
+    if let Some(kind) = cand.dynamic {
+        words.push(format!(
+            "${{(f)\"$({} completions --values {})\"}}",
+            bin, kind
+        ));
+    }
+    if !words.is_empty() {
+        lines.push(format!("{}compadd -- {}", indent, words.join(" ")));
+    }
+    if cand.files {
+        lines.push(format!("{}_files", indent));
+    }
+    lines
+}
+
+fn zsh_script(nodes: &[Node], func: &str, bin: &str) -> Vec<String> {
+    let mut lines = vec![
+        format!("#compdef {}", bin),
+        "".to_string(),
+        format!("_{}() {{", func),
+        format!("    local cmd=\"{}\" i", nodes[0].key),
+        "    for ((i = 2; i < CURRENT; i++)); do".to_string(),
+        "        case \"${cmd}:${words[i]}\" in".to_string(),
+    ];
+    for (from, to) in transitions(nodes) {
+        lines.push(format!("            {}) cmd=\"{}\" ;;", from, to));
+    }
+    lines.push("        esac".to_string());
+    lines.push("    done".to_string());
+    lines.push("    case \"${cmd}\" in".to_string());
+    for node in nodes {
+        lines.push(format!("        {})", node.key));
+        let valued: Vec<_> = node.opns.iter().filter(|o| takes_value(o)).collect();
+        if !valued.is_empty() {
+            lines.push("            case \"${words[CURRENT-1]}\" in".to_string());
+            for opn in valued {
+                lines.push(format!("                {})", opn.flags().join("|")));
+                let cand = Candidates::of(&opn.vtype);
+                lines.extend(zsh_compadd(&cand, &[], bin, "                    "));
+                lines.push("                    return ;;".to_string());
+            }
+            lines.push("            esac".to_string());
+        }
+        let mut words: Vec<String> = node.opns.iter().flat_map(|o| o.flags()).collect();
+        words.extend(node.subcmds.iter().map(|(s, _)| s.to_string()));
+        let cand = node.value.clone().unwrap_or_default();
+        lines.extend(zsh_compadd(&cand, &words, bin, "            "));
+        lines.push("            ;;".to_string());
+    }
+    lines.push("    esac".to_string());
+    lines.push("}".to_string());
+    lines.push("".to_string());
+    lines.push(format!("if [ \"$funcstack[1]\" = \"_{}\" ]; then", func));
+    lines.push(format!("    _{} \"$@\"", func));
+    lines.push("else".to_string());
+    lines.push(format!("    compdef _{} {}", func, bin));
+    lines.push("fi".to_string());
+    lines
+}
+
+fn fish_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+fn fish_values(cand: &Candidates, bin: &str) -> String {
+    let mut args = String::new();
+    let mut words = cand.words.join(" ");
+    if let Some(kind) = cand.dynamic {
+        words = format!("{} ({} completions --values {})", words, bin, kind);
+    }
+    if !words.trim().is_empty() {
+        args.push_str(&format!(" -a {}", fish_quote(words.trim())));
+    }
+    if cand.files {
+        args.push_str(" -F");
+    }
+    args
+}
+
+fn fish_script(nodes: &[Node], func: &str, bin: &str) -> Vec<String> {
+    let mut lines = vec![
+        format!("# fish completion for {}", bin),
+        format!("function __{}_cmd", func),
+        format!("    set -l cmd {}", nodes[0].key),
+        "    for w in (commandline -opc)[2..-1]".to_string(),
+        "        switch \"$cmd:$w\"".to_string(),
+    ];
+    for (from, to) in transitions(nodes) {
+        lines.push(format!("            case {}", fish_quote(&from)));
+        lines.push(format!("                set cmd {}", to));
+    }
+    lines.push("        end".to_string());
+    lines.push("    end".to_string());
+    lines.push("    echo $cmd".to_string());
+    lines.push("end".to_string());
+    lines.push("".to_string());
+    lines.push(format!("complete -c {} -f", bin));
+    for node in nodes {
+        let cond = format!("-n 'test (__{}_cmd) = {}'", func, node.key);
+        for opn in &node.opns {
+            let mut line = format!("complete -c {} {}", bin, cond);
+            if !opn.short.is_empty() {
+                line.push_str(&format!(" -s {}", opn.short));
+            }
+            if !opn.long.is_empty() {
+                line.push_str(&format!(" -l {}", opn.long));
+            }
+            if takes_value(opn) {
+                line.push_str(" -r");
+                line.push_str(&fish_values(&Candidates::of(&opn.vtype), bin));
+            }
+            line.push_str(&format!(" -d {}", fish_quote(opn.title)));
+            lines.push(line);
+        }
+        if let Some(cand) = &node.value {
+            let values = fish_values(cand, bin);
+            if !values.is_empty() {
+                lines.push(format!("complete -c {} {}{}", bin, cond, values));
+            }
+        }
+        for (name, title) in &node.subcmds {
+            lines.push(format!(
+                "complete -c {} {} -a {} -d {}",
+                bin,
+                cond,
+                fish_quote(name),
+                fish_quote(title)
+            ));
+        }
+    }
+    lines
+}
@@ -0,0 +1,415 @@
+/// Config file layer under CLI flags
+/// Defines:
+///   files   : project-local .ccc.toml, seeked from current directory up to file system root,
+///             then user-level $XDG_CONFIG_HOME/ccc/config.toml or ~/.config/ccc/config.toml
+///   keys    : top level keys are root command's option long names,
+///             a table is a sub command, its keys are the sub command's option long names.
+///   values  : string, integer, float or bool; array for repeatable option;
+///             bool for flag option(ValueSpec::None), true means present.
+///   precedence: argv > env var > project config > user config > default value
+/// Sample(.ccc.toml):
+///   dir = "io_files"
+///   [run]
+///   testcase = ["tc1", "tc2"]
+///   limit = "2s"
+///
+use crate::cli::cmd::{Command, OpnSepc, ValueSource, ValueSpec};
+use crate::cli::suggest::did_you_mean;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+/// project-local config file name
+pub const PROJECT_FILE: &str = ".ccc.toml";
+
+/// Merged config files, higher precedence entries first
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    entries: Vec<CfgEntry>,
+    files: Vec<(PathBuf, ValueSource)>,
+}
+
+/// One option's values in config file
+#[derive(Debug, Clone)]
+struct CfgEntry {
+    path: Vec<String>, // sub command names under root command
+    key: String,       // option long name
+    values: Vec<String>,
+    source: ValueSource,
+}
+
+/// Config file load or check error
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    Io(String, String),                 // file, reason
+    Syntax(String, String),             // file, reason
+    UnknownKey(String, Option<String>), // key, suggested key
+    NotRepeatable(String),
+    InvalidValue {
+        key: String,
+        value: String,
+        expect: String,
+    },
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Io(file, reason) => {
+                write!(f, "can not read config '{}': {}", file, reason)
+            }
+            ConfigError::Syntax(file, reason) => write!(f, "invalid config '{}': {}", file, reason),
+            ConfigError::UnknownKey(key, suggest) => {
+                write!(f, "unknown config key '{}'", key)?;
+                match suggest {
+                    Some(s) => write!(f, ", did you mean '{}'?", s),
+                    None => Ok(()),
+                }
+            }
+            ConfigError::NotRepeatable(key) => {
+                write!(f, "config key '{}' takes one value only", key)
+            }
+            ConfigError::InvalidValue { key, value, expect } => write!(
+                f,
+                "invalid value '{}' for config key '{}': expect {}",
+                value, key, expect
+            ),
+        }
+    }
+}
+
+impl Config {
+    /// Load project config, then user config under it.
+    ///   absent files are skipped.
+    pub fn load() -> Result<Config, ConfigError> {
+        let mut config = Config::default();
+        if let Some(path) = env::current_dir()
+            .ok()
+            .and_then(|dir| Config::project_file(&dir))
+        {
+            config = config.merge(Config::load_file(&path, ValueSource::ProjectConfig)?);
+        }
+        if let Some(path) = Config::user_file().filter(|p| p.is_file()) {
+            config = config.merge(Config::load_file(&path, ValueSource::UserConfig)?);
+        }
+        Ok(config)
+    }
+
+    /// Seek project config file from dir up to file system root
+    pub fn project_file(dir: &Path) -> Option<PathBuf> {
+        dir.ancestors()
+            .map(|d| d.join(PROJECT_FILE))
+            .find(|p| p.is_file())
+    }
+
+    /// User config file path, may not exist
+    pub fn user_file() -> Option<PathBuf> {
+        let base = match env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => PathBuf::from(env::var_os("HOME")?).join(".config"),
+        };
+        Some(base.join("ccc").join("config.toml"))
+    }
+
+    /// Load one config file
+    pub fn load_file(path: &Path, source: ValueSource) -> Result<Config, ConfigError> {
+        let file = path.display().to_string();
+        let text =
+            fs::read_to_string(path).map_err(|e| ConfigError::Io(file.clone(), e.to_string()))?;
+        let mut config = Config::parse(&text, source).map_err(|e| match e {
+            ConfigError::Syntax(_, reason) => ConfigError::Syntax(file, reason),
+            e => e,
+        })?;
+        config.files.push((path.to_path_buf(), source));
+        Ok(config)
+    }
+
+    /// Parse config text in TOML
+    pub fn parse(text: &str, source: ValueSource) -> Result<Config, ConfigError> {
+        let table = text
+            .parse::<toml::Table>()
+            .map_err(|e| ConfigError::Syntax(String::new(), e.message().to_string()))?;
+        let mut config = Config::default();
+        config.collect(&table, &mut Vec::new(), source)?;
+        Ok(config)
+    }
+
+    /// Merge a lower precedence config under self
+    pub fn merge(mut self, lower: Config) -> Config {
+        self.entries.extend(lower.entries);
+        self.files.extend(lower.files);
+        self
+    }
+
+    /// Loaded config files with their source, higher precedence first
+    pub fn files(&self) -> &[(PathBuf, ValueSource)] {
+        &self.files
+    }
+
+    /// Option's values in config of command path
+    ///   path: sub command names under root command.
+    ///   flag option has one None value if present.
+    pub(crate) fn values(
+        &self,
+        path: &[&str],
+        spec: &OpnSepc,
+    ) -> Option<(Vec<Option<String>>, ValueSource)> {
+        let entry = self
+            .entries
+            .iter()
+            .find(|e| !spec.long.is_empty() && e.key == spec.long && e.path == path)?;
+        let values = match spec.vspec {
+            ValueSpec::None if entry.values == ["true"] => vec![None],
+            ValueSpec::None => return None,
+            _ => entry.values.iter().cloned().map(Some).collect(),
+        };
+        Some((values, entry.source))
+    }
+
+    /// Check every config key is a registered option long name, and its values are valid.
+    pub(crate) fn check(&self, rtcmd: &Command) -> Result<(), ConfigError> {
+        for entry in &self.entries {
+            let mut cmd = rtcmd;
+            for (depth, name) in entry.path.iter().enumerate() {
+                cmd = match cmd.subcmds().iter().find(|c| c.try_match(name)) {
+                    Some(sub) => sub,
+                    None => {
+                        let names = cmd.subcmds().iter().map(|c| c.spec().name);
+                        let suggest =
+                            did_you_mean(name, names).map(|s| dotted_key(&entry.path[..depth], s));
+                        return Err(ConfigError::UnknownKey(
+                            dotted_key(&entry.path[..depth], name),
+                            suggest,
+                        ));
+                    }
+                };
+            }
+            let key = dotted_key(&entry.path, &entry.key);
+            let spec = match cmd.options().iter().find(|o| o.spec().long == entry.key) {
+                Some(opn) => opn.spec(),
+                None => {
+                    let names = cmd.options().iter().map(|o| o.spec().long);
+                    let suggest =
+                        did_you_mean(&entry.key, names).map(|s| dotted_key(&entry.path, s));
+                    return Err(ConfigError::UnknownKey(key, suggest));
+                }
+            };
+            if entry.values.len() > 1 && !spec.repeat {
+                return Err(ConfigError::NotRepeatable(key));
+            }
+            for value in &entry.values {
+                let valid = match spec.vspec {
+                    ValueSpec::None => value == "true" || value == "false",
+                    _ => spec.vtype.check(value),
+                };
+                if !valid {
+                    let expect = match spec.vspec {
+                        ValueSpec::None => "true or false".to_string(),
+                        _ => spec.vtype.expect(),
+                    };
+                    return Err(ConfigError::InvalidValue {
+                        key,
+                        value: value.clone(),
+                        expect,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Collect option entries of table, recursive into sub command tables
+    fn collect(
+        &mut self,
+        table: &toml::Table,
+        path: &mut Vec<String>,
+        source: ValueSource,
+    ) -> Result<(), ConfigError> {
+        for (key, value) in table {
+            if let toml::Value::Table(sub) = value {
+                path.push(key.clone());
+                self.collect(sub, path, source)?;
+                path.pop();
+                continue;
+            }
+            let items = match value {
+                toml::Value::Array(items) => items.iter().collect(),
+                value => vec![value],
+            };
+            let values = items
+                .into_iter()
+                .map(|item| match item {
+                    toml::Value::String(s) => Ok(s.clone()),
+                    toml::Value::Table(_) | toml::Value::Array(_) => Err(ConfigError::Syntax(
+                        String::new(),
+                        format!("'{}' has nested value", dotted_key(path, key)),
+                    )),
+                    item => Ok(item.to_string()),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            self.entries.push(CfgEntry {
+                path: path.clone(),
+                key: key.clone(),
+                values,
+                source,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// config key with sub command path. eg. run.limit
+pub fn dotted_key(path: &[impl AsRef<str>], key: &str) -> String {
+    path.iter()
+        .map(|name| name.as_ref())
+        .chain([key])
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cli::cmd::CmdExeArgs;
+    use crate::cli::value::ValueType;
+
+    fn default_cmder(_cmd_args: CmdExeArgs) -> Result<i32, String> {
+        Ok(0)
+    }
+
+    fn build_commands() -> Command {
+        crate::command_tree!(
+            "ccc" => default_cmder, {},
+            options: [{
+                long: "dir",
+                vspec: ValueSpec::Must,
+                vtype: ValueType::Path,
+                env: "CCC_CONFIG_TEST_DIR",
+            }],
+            subcmds: [(
+                "run" => default_cmder, { vspec: ValueSpec::Maybe },
+                options: [
+                    {
+                        short: "t",
+                        long: "testcase",
+                        vspec: ValueSpec::Must,
+                        vtype: ValueType::Testcase,
+                        repeat: true,
+                    },
+                    {
+                        long: "limit",
+                        vspec: ValueSpec::Must,
+                        vtype: ValueType::Duration,
+                        default: "1s",
+                    },
+                    { short: "q", long: "quiet" },
+                ],
+            )],
+        )
+    }
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn config_parse_and_check() {
+        let rtcmd = build_commands();
+        let text = "dir = \"src\"\n[run]\ntestcase = [\"tc1\", \"tc2\"]\nquiet = true\n";
+        let config = Config::parse(text, ValueSource::ProjectConfig).unwrap();
+        assert_eq!(config.check(&rtcmd), Ok(()));
+
+        let check = |text: &str| {
+            Config::parse(text, ValueSource::UserConfig)
+                .and_then(|config| config.check(&rtcmd))
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            check("[run]\nlimt = \"2s\""),
+            "unknown config key 'run.limt', did you mean 'run.limit'?"
+        );
+        assert_eq!(
+            check("[rnu]\nlimit = \"2s\""),
+            "unknown config key 'rnu', did you mean 'run'?"
+        );
+        assert_eq!(
+            check("[run]\nlimit = \"fast\""),
+            "invalid value 'fast' for config key 'run.limit': expect duration like 2s, 500ms"
+        );
+        assert_eq!(
+            check("[run]\nlimit = [\"1s\", \"2s\"]"),
+            "config key 'run.limit' takes one value only"
+        );
+        assert_eq!(
+            check("[run]\nquiet = 1"),
+            "invalid value '1' for config key 'run.quiet': expect true or false"
+        );
+        assert!(check("dir = ").starts_with("invalid config ''"));
+    }
+
+    #[test]
+    fn config_precedence() {
+        let project = "[run]\nlimit = \"2s\"\n";
+        let user = "dir = \"src\"\n[run]\nlimit = \"3s\"\ntestcase = \"tc9\"\nquiet = true\n";
+        let config = Config::parse(project, ValueSource::ProjectConfig)
+            .unwrap()
+            .merge(Config::parse(user, ValueSource::UserConfig).unwrap());
+        let mut rtcmd = build_commands();
+        rtcmd.set_config(config).unwrap();
+
+        let cmd_args = rtcmd.parse(argv(&["ccc", "run"])).unwrap();
+        assert_eq!(cmd_args.opn_value("--limit"), Some("2s"));
+        assert_eq!(
+            cmd_args.opn_source("--limit"),
+            Some(ValueSource::ProjectConfig)
+        );
+        assert_eq!(cmd_args.opn_values("-t"), vec!["tc9"]);
+        assert_eq!(cmd_args.opn_source("-t"), Some(ValueSource::UserConfig));
+        assert!(cmd_args.has_opn("-q"));
+        assert_eq!(cmd_args.opn_value("--dir"), Some("src"));
+
+        let cmd_args = rtcmd
+            .parse(argv(&["ccc", "run", "--limit", "5s", "-t", "tc1"]))
+            .unwrap();
+        assert_eq!(cmd_args.opn_value("--limit"), Some("5s"));
+        assert_eq!(cmd_args.opn_values("-t"), vec!["tc1"]);
+        assert_eq!(cmd_args.opn_source("-t"), Some(ValueSource::Argv));
+
+        env::set_var("CCC_CONFIG_TEST_DIR", "src/cli");
+        let cmd_args = rtcmd.parse(argv(&["ccc", "run"])).unwrap();
+        assert_eq!(cmd_args.opn_value("--dir"), Some("src/cli"));
+        env::remove_var("CCC_CONFIG_TEST_DIR");
+
+        let effective = rtcmd.effective_config().unwrap();
+        let lines: Vec<String> = effective
+            .iter()
+            .map(|(key, values, source)| format!("{} = {} ({})", key, values.join(", "), source))
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                "dir = src (user config)",
+                "run.testcase = tc9 (user config)",
+                "run.limit = 2s (project config)",
+                "run.quiet = true (user config)",
+            ]
+        );
+    }
+
+    #[test]
+    fn config_files() {
+        let dir = env::temp_dir().join(format!("ccc_config_test_{}", std::process::id()));
+        let sub = dir.join("a").join("b");
+        fs::create_dir_all(&sub).unwrap();
+        fs::write(dir.join(PROJECT_FILE), "[run]\nlimit = \"2s\"\n").unwrap();
+        let path = Config::project_file(&sub).unwrap();
+        assert_eq!(path, dir.join(PROJECT_FILE));
+        let config = Config::load_file(&path, ValueSource::ProjectConfig).unwrap();
+        assert_eq!(config.files(), [(path, ValueSource::ProjectConfig)]);
+        fs::write(dir.join(PROJECT_FILE), "[run\n").unwrap();
+        let err = Config::load_file(&dir.join(PROJECT_FILE), ValueSource::ProjectConfig);
+        assert!(matches!(err, Err(ConfigError::Syntax(file, _)) if file.ends_with(PROJECT_FILE)));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,451 @@
+/// Command documents rendering
+/// Defines:
+///   CmdDoc   : document model of a command and its visible sub commands.
+///   DocRender: render CmdDoc into lines of a document format.
+///     TermDoc    : terminal help, wrapped to terminal width.
+///     ManDoc     : roff man page, one page per command.
+///     MarkdownDoc: Markdown CLI reference of the whole command tree.
+/// Sample:
+///   TermDoc::new(80).render(&CmdDoc::new(&rtcmd))
+///
+use crate::cli::cmd::{CmdSpec, Command, OpnSepc};
+
+/// Default document width when terminal width is unknown
+const DEFAULT_WIDTH: usize = 80;
+
+/// Option document
+#[derive(Debug, Clone)]
+pub struct OpnDoc {
+    pub short: &'static str,
+    pub long: &'static str,
+    pub value: String, // value doc. eg. VALUE, [QID]
+    pub title: &'static str,
+    pub notes: Vec<String>, // eg. [default: 1s], [env: CCC_DIR]
+}
+
+/// Command document
+#[derive(Debug, Clone)]
+pub struct CmdDoc {
+    pub path: Vec<&'static str>, // command names from root. eg. ["ccc", "run"]
+    pub spec: CmdSpec<'static>,
+    pub value: String,
+    pub options: Vec<OpnDoc>,
+    pub subcmds: Vec<CmdDoc>, // visible sub commands only
+}
+
+/// Render command document to lines
+pub trait DocRender {
+    fn render(&self, doc: &CmdDoc) -> Vec<String>;
+}
+
+impl OpnDoc {
+    fn new(spec: &OpnSepc<'static>) -> OpnDoc {
+        let mut notes = Vec::new();
+        if !spec.default.is_empty() {
+            notes.push(format!("[default: {}]", spec.default));
+        }
+        if !spec.env.is_empty() {
+            notes.push(format!("[env: {}]", spec.env));
+        }
+        if spec.repeat {
+            notes.push("[repeatable]".to_string());
+        }
+        OpnDoc {
+            short: spec.short,
+            long: spec.long,
+            value: spec.vspec.doc(&spec.vtype),
+            title: spec.title,
+            notes,
+        }
+    }
+
+    /// CLI flags. eg. ["-t", "--testcase"]
+    pub fn flags(&self) -> Vec<String> {
+        OpnSepc {
+            short: self.short,
+            long: self.long,
+            ..OpnSepc::EMPTY
+        }
+        .flags()
+    }
+
+    /// title with notes
+    pub fn full_title(&self) -> String {
+        let mut title = self.title.to_string();
+        for note in &self.notes {
+            title.push(' ');
+            title.push_str(note);
+        }
+        title
+    }
+}
+
+impl CmdDoc {
+    /// make document model of cmd and its visible sub commands
+    pub fn new(cmd: &Command) -> CmdDoc {
+        CmdDoc::with_parent(cmd, &[])
+    }
+
+    fn with_parent(cmd: &Command, parent: &[&'static str]) -> CmdDoc {
+        let mut path = parent.to_vec();
+        path.push(cmd.spec().name);
+        CmdDoc {
+            spec: *cmd.spec(),
+            value: cmd.spec().vspec.doc(&cmd.spec().vtype),
+            options: cmd
+                .options()
+                .iter()
+                .map(|o| OpnDoc::new(o.spec()))
+                .collect(),
+            subcmds: cmd
+                .subcmds()
+                .iter()
+                .filter(|c| !c.is_hidden())
+                .map(|c| CmdDoc::with_parent(c, &path))
+                .collect(),
+            path,
+        }
+    }
+
+    /// self and all descendant command documents, depth first
+    pub fn walk(&self) -> Vec<&CmdDoc> {
+        let mut docs = vec![self];
+        for subcmd in &self.subcmds {
+            docs.extend(subcmd.walk());
+        }
+        docs
+    }
+}
+
+/// Current terminal width, COLUMNS env var overrides it.
+pub fn term_width() -> usize {
+    if let Some(width) = std::env::var("COLUMNS").ok().and_then(|c| c.parse().ok()) {
+        return width;
+    }
+    match terminal_size::terminal_size() {
+        Some((terminal_size::Width(w), _)) => w as usize,
+        None => DEFAULT_WIDTH,
+    }
+}
+
+/// Wrap text by words into lines no longer than width.
+///   a word longer than width takes a line by itself.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Terminal help document
+pub struct TermDoc {
+    width: usize,
+}
+
+impl TermDoc {
+    pub fn new(width: usize) -> TermDoc {
+        TermDoc {
+            width: width.max(40),
+        }
+    }
+
+    /// "   Label : text" with wrapped text aligned after the label
+    fn field(&self, doc: &mut Vec<String>, label: &str, text: &str) {
+        for (idx, line) in wrap(text, self.width - 11).iter().enumerate() {
+            let label = if idx == 0 { label } else { "" };
+            let sep = if idx == 0 { ":" } else { " " };
+            doc.push(
+                format!("{:>8} {} {}", label, sep, line)
+                    .trim_end()
+                    .to_string(),
+            );
+        }
+    }
+
+    /// sub command tree with usage and description
+    fn tree(&self, doc: &mut Vec<String>, cmd: &CmdDoc, depth: usize) {
+        for subcmd in &cmd.subcmds {
+            let indent = "  ".repeat(depth);
+            let head = format!("{} {}", subcmd.spec.name, subcmd.value);
+            doc.push(format!(
+                "{}{} : {}",
+                indent,
+                head.trim_end(),
+                subcmd.spec.title
+            ));
+            let width = self.width.saturating_sub(indent.len() + 4).max(20);
+            for line in wrap(&format!("usage: {}", subcmd.spec.usage), width) {
+                doc.push(format!("{}    {}", indent, line));
+            }
+            if !subcmd.spec.desc.is_empty() {
+                for line in wrap(subcmd.spec.desc, width) {
+                    doc.push(format!("{}    {}", indent, line));
+                }
+            }
+            self.tree(doc, subcmd, depth + 1);
+        }
+    }
+}
+
+impl Default for TermDoc {
+    fn default() -> Self {
+        TermDoc::new(term_width())
+    }
+}
+
+impl DocRender for TermDoc {
+    fn render(&self, cmd: &CmdDoc) -> Vec<String> {
+        let mut doc: Vec<String> = Vec::new();
+
+        // 1) command self document
+        doc.push(format!("{:-^50}", "Brife Info"));
+        self.field(&mut doc, "Name", cmd.spec.name);
+        self.field(&mut doc, "Title", cmd.spec.title);
+        self.field(&mut doc, "Usage", cmd.spec.usage);
+        self.field(&mut doc, "Value", &cmd.value);
+        if !cmd.spec.desc.is_empty() {
+            self.field(&mut doc, "Desc", cmd.spec.desc);
+        }
+
+        // 2) options brife document
+        if !cmd.options.is_empty() {
+            doc.push(format!("{:-^50}", "Options"));
+            for option in &cmd.options {
+                let flag = |x: &str, p: &str| {
+                    if x.is_empty() {
+                        "".to_string()
+                    } else {
+                        format!("{}{}", p, x)
+                    }
+                };
+                doc.push(format!(
+                    "{:4} {:10} {:6} {}",
+                    flag(option.short, "-"),
+                    flag(option.long, "--"),
+                    option.value,
+                    option.full_title()
+                ));
+            }
+        }
+
+        // 3) sub commands brife document
+        if !cmd.subcmds.is_empty() {
+            doc.push(format!("{:-^50}", "Sub Commands"));
+            for subcmd in &cmd.subcmds {
+                doc.push(format!(
+                    "{:>10} {:6} {}",
+                    subcmd.spec.name, subcmd.value, subcmd.spec.title
+                ));
+            }
+            doc.push(format!("{:-^50}", "Command Tree"));
+            self.tree(&mut doc, cmd, 0);
+        }
+        doc
+    }
+}
+
+/// roff man page document, section 1
+pub struct ManDoc;
+
+impl ManDoc {
+    /// man page file name of cmd. eg. ccc-run.1
+    pub fn file_name(cmd: &CmdDoc) -> String {
+        format!("{}.1", cmd.path.join("-"))
+    }
+
+    /// man pages of cmd and all descendant commands: (file name, page lines)
+    pub fn pages(cmd: &CmdDoc) -> Vec<(String, Vec<String>)> {
+        cmd.walk()
+            .into_iter()
+            .map(|c| (ManDoc::file_name(c), ManDoc.render(c)))
+            .collect()
+    }
+}
+
+/// escape text for roff
+fn roff(text: &str) -> String {
+    let text = text.replace('\\', "\\e").replace('-', "\\-");
+    if text.starts_with('.') || text.starts_with('\'') {
+        format!("\\&{}", text)
+    } else {
+        text
+    }
+}
+
+impl DocRender for ManDoc {
+    fn render(&self, cmd: &CmdDoc) -> Vec<String> {
+        let mut doc = vec![
+            format!(".TH {} 1", roff(&cmd.path.join("-").to_uppercase())),
+            ".SH NAME".to_string(),
+            format!("{} \\- {}", roff(&cmd.path.join("-")), roff(cmd.spec.title)),
+            ".SH SYNOPSIS".to_string(),
+            format!(".B {}", roff(&cmd.path.join(" "))),
+        ];
+        let mut synopsis = Vec::new();
+        if !cmd.options.is_empty() {
+            synopsis.push("[\\fIOPTIONS\\fR]".to_string());
+        }
+        if !cmd.value.is_empty() {
+            synopsis.push(format!("\\fI{}\\fR", roff(&cmd.value)));
+        }
+        if !cmd.subcmds.is_empty() {
+            synopsis.push("[\\fICOMMAND\\fR]".to_string());
+        }
+        if !synopsis.is_empty() {
+            doc.push(synopsis.join(" "));
+        }
+        doc.push(".SH DESCRIPTION".to_string());
+        doc.push(roff(if cmd.spec.desc.is_empty() {
+            cmd.spec.title
+        } else {
+            cmd.spec.desc
+        }));
+        doc.push(".SH USAGE".to_string());
+        doc.push(".nf".to_string());
+        doc.push(roff(cmd.spec.usage));
+        doc.push(".fi".to_string());
+        if !cmd.options.is_empty() {
+            doc.push(".SH OPTIONS".to_string());
+            for option in &cmd.options {
+                doc.push(".TP".to_string());
+                let flags: Vec<String> = option
+                    .flags()
+                    .iter()
+                    .map(|f| format!("\\fB{}\\fR", roff(f)))
+                    .collect();
+                let mut head = flags.join(", ");
+                if !option.value.is_empty() {
+                    head.push_str(&format!(" \\fI{}\\fR", roff(&option.value)));
+                }
+                doc.push(head);
+                doc.push(roff(&option.full_title()));
+            }
+        }
+        if !cmd.subcmds.is_empty() {
+            doc.push(".SH COMMANDS".to_string());
+            for subcmd in &cmd.subcmds {
+                doc.push(".TP".to_string());
+                doc.push(format!("\\fB{}\\fR", roff(subcmd.spec.name)));
+                doc.push(format!(
+                    "{} See \\fB{}\\fR(1).",
+                    roff(subcmd.spec.title),
+                    roff(&subcmd.path.join("-"))
+                ));
+            }
+        }
+        if cmd.path.len() > 1 {
+            doc.push(".SH SEE ALSO".to_string());
+            doc.push(format!(
+                "\\fB{}\\fR(1)",
+                roff(&cmd.path[..cmd.path.len() - 1].join("-"))
+            ));
+        }
+        doc
+    }
+}
+
+/// Markdown CLI reference document
+pub struct MarkdownDoc;
+
+/// escape text in Markdown table cell
+fn md_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+/// `code` span, empty text for empty cell
+fn md_code(text: &str) -> String {
+    if text.is_empty() {
+        "".to_string()
+    } else {
+        format!("`{}`", md_cell(text))
+    }
+}
+
+impl DocRender for MarkdownDoc {
+    fn render(&self, cmd: &CmdDoc) -> Vec<String> {
+        let mut doc = Vec::new();
+        for (idx, c) in cmd.walk().into_iter().enumerate() {
+            if idx > 0 {
+                doc.push("".to_string());
+            }
+            doc.push(format!("{} {}", "#".repeat(c.path.len()), c.path.join(" ")));
+            doc.push("".to_string());
+            doc.push(c.spec.title.to_string());
+            if !c.spec.desc.is_empty() {
+                doc.push("".to_string());
+                doc.push(c.spec.desc.to_string());
+            }
+            doc.push("".to_string());
+            doc.push(format!("**Usage:** {}", md_code(c.spec.usage)));
+            if !c.value.is_empty() {
+                doc.push("".to_string());
+                doc.push(format!("**Value:** {}", md_code(&c.value)));
+            }
+            if !c.options.is_empty() {
+                doc.push("".to_string());
+                doc.push("| Flag | Value | Description |".to_string());
+                doc.push("| --- | --- | --- |".to_string());
+                for option in &c.options {
+                    let flags: Vec<String> = option.flags().iter().map(|f| md_code(f)).collect();
+                    doc.push(format!(
+                        "| {} | {} | {} |",
+                        flags.join(", "),
+                        md_code(&option.value),
+                        md_cell(&option.full_title())
+                    ));
+                }
+            }
+            if !c.subcmds.is_empty() {
+                doc.push("".to_string());
+                doc.push("| Command | Value | Description |".to_string());
+                doc.push("| --- | --- | --- |".to_string());
+                for subcmd in &c.subcmds {
+                    doc.push(format!(
+                        "| [`{}`](#{}) | {} | {} |",
+                        subcmd.spec.name,
+                        subcmd.path.join("-"),
+                        md_code(&subcmd.value),
+                        md_cell(subcmd.spec.title)
+                    ));
+                }
+            }
+        }
+        doc
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn wrap_words() {
+        assert_eq!(
+            wrap("run ccc question solvment", 10),
+            vec!["run ccc", "question", "solvment"]
+        );
+        assert_eq!(
+            wrap("averyveryverylongword x", 5),
+            vec!["averyveryverylongword", "x"]
+        );
+        assert_eq!(wrap("", 5), vec![""]);
+    }
+
+    #[test]
+    fn roff_escape() {
+        assert_eq!(roff("--dir"), "\\-\\-dir");
+        assert_eq!(roff(".hidden"), "\\&.hidden");
+        assert_eq!(roff("a\\b"), "a\\eb");
+    }
+}
@@ -1 +1,7 @@
 pub mod cmd;
+pub mod config;
+pub mod complete;
+pub mod doc;
+pub mod suggest;
+pub mod tree;
+pub mod value;
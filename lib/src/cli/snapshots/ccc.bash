@@ -0,0 +1,60 @@
+# bash completion for ccc
+_ccc() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    local prev="${COMP_WORDS[COMP_CWORD-1]}"
+    local cmd="ccc" i
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${cmd}:${COMP_WORDS[i]}" in
+            ccc:run) cmd="ccc_run" ;;
+            ccc:search) cmd="ccc_search" ;;
+            ccc_run:all) cmd="ccc_run_all" ;;
+        esac
+    done
+    case "${cmd}" in
+        ccc)
+            case "${prev}" in
+                --dir)
+                    COMPREPLY=($(compgen -f -- "${cur}"))
+                    return ;;
+            esac
+            COMPREPLY=($(compgen -W "-h --help --dir run search" -- "${cur}"))
+            ;;
+        ccc_run)
+            case "${prev}" in
+                -t|--testcase)
+                    COMPREPLY=($(compgen -W "$(ccc completions --values tc)" -- "${cur}"))
+                    return ;;
+                -l|--limit)
+                    COMPREPLY=()
+                    return ;;
+                --dir)
+                    COMPREPLY=($(compgen -f -- "${cur}"))
+                    return ;;
+            esac
+            COMPREPLY=($(compgen -W "-t --testcase -l --limit -h --help --dir all $(ccc completions --values qid)" -- "${cur}"))
+            ;;
+        ccc_run_all)
+            case "${prev}" in
+                -t|--testcase)
+                    COMPREPLY=($(compgen -W "$(ccc completions --values tc)" -- "${cur}"))
+                    return ;;
+                -l|--limit)
+                    COMPREPLY=()
+                    return ;;
+                --dir)
+                    COMPREPLY=($(compgen -f -- "${cur}"))
+                    return ;;
+            esac
+            COMPREPLY=($(compgen -W "-t --testcase -l --limit -h --help --dir" -- "${cur}"))
+            ;;
+        ccc_search)
+            case "${prev}" in
+                --dir)
+                    COMPREPLY=($(compgen -f -- "${cur}"))
+                    return ;;
+            esac
+            COMPREPLY=($(compgen -W "-i --letter-case -h --help --dir" -- "${cur}"))
+            ;;
+    esac
+}
+complete -F _ccc ccc
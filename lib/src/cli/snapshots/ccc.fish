@@ -0,0 +1,34 @@
+# fish completion for ccc
+function __ccc_cmd
+    set -l cmd ccc
+    for w in (commandline -opc)[2..-1]
+        switch "$cmd:$w"
+            case 'ccc:run'
+                set cmd ccc_run
+            case 'ccc:search'
+                set cmd ccc_search
+            case 'ccc_run:all'
+                set cmd ccc_run_all
+        end
+    end
+    echo $cmd
+end
+
+complete -c ccc -f
+complete -c ccc -n 'test (__ccc_cmd) = ccc' -s h -l help -d 'help information about application'
+complete -c ccc -n 'test (__ccc_cmd) = ccc' -l dir -r -F -d 'assign testcase base direction'
+complete -c ccc -n 'test (__ccc_cmd) = ccc' -a 'run' -d 'run ccc question solvment'
+complete -c ccc -n 'test (__ccc_cmd) = ccc' -a 'search' -d 'Search ccc question'
+complete -c ccc -n 'test (__ccc_cmd) = ccc_run' -s t -l testcase -r -a '(ccc completions --values tc)' -d 'testcase file name'
+complete -c ccc -n 'test (__ccc_cmd) = ccc_run' -s l -l limit -r -d 'time limit of each testcase'
+complete -c ccc -n 'test (__ccc_cmd) = ccc_run' -s h -l help -d 'help information about application'
+complete -c ccc -n 'test (__ccc_cmd) = ccc_run' -l dir -r -F -d 'assign testcase base direction'
+complete -c ccc -n 'test (__ccc_cmd) = ccc_run' -a '(ccc completions --values qid)'
+complete -c ccc -n 'test (__ccc_cmd) = ccc_run' -a 'all' -d 'run all ccc questions'
+complete -c ccc -n 'test (__ccc_cmd) = ccc_run_all' -s t -l testcase -r -a '(ccc completions --values tc)' -d 'testcase file name'
+complete -c ccc -n 'test (__ccc_cmd) = ccc_run_all' -s l -l limit -r -d 'time limit of each testcase'
+complete -c ccc -n 'test (__ccc_cmd) = ccc_run_all' -s h -l help -d 'help information about application'
+complete -c ccc -n 'test (__ccc_cmd) = ccc_run_all' -l dir -r -F -d 'assign testcase base direction'
+complete -c ccc -n 'test (__ccc_cmd) = ccc_search' -s i -l letter-case -d 'Search by letter case sentitive'
+complete -c ccc -n 'test (__ccc_cmd) = ccc_search' -s h -l help -d 'help information about application'
+complete -c ccc -n 'test (__ccc_cmd) = ccc_search' -l dir -r -F -d 'assign testcase base direction'
@@ -0,0 +1,73 @@
+// "Did you mean" suggestions for mistyped names
+// Sample:
+//   ccc serach     => did you mean 'search'?
+//   ccc run 03s4   => did you mean '03s3'?
+//
+
+/// Edit distance between a and b (optimal string alignment), ignoring letter case.
+/// insert, delete, replace and swap of adjacent chars cost 1.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let mut dist = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in dist.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in dist[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            dist[i][j] = (dist[i - 1][j] + 1)
+                .min(dist[i][j - 1] + 1)
+                .min(dist[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                dist[i][j] = dist[i][j].min(dist[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    dist[a.len()][b.len()]
+}
+
+/// Seek the closest candidate to input.
+///   a candidate is close enough if edit distance <= 1/3 of input length.
+pub fn did_you_mean<'a>(
+    input: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let limit = input.chars().count() / 3;
+    candidates
+        .into_iter()
+        .filter(|c| !c.is_empty())
+        .map(|c| (edit_distance(input, c), c))
+        .filter(|(dist, _)| *dist <= limit)
+        .min_by_key(|(dist, _)| *dist)
+        .map(|(_, c)| c)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn edit_distance_cases() {
+        assert_eq!(edit_distance("search", "search"), 0);
+        assert_eq!(edit_distance("serach", "search"), 1);
+        assert_eq!(edit_distance("Search", "search"), 0);
+        assert_eq!(edit_distance("run", "ran"), 1);
+        assert_eq!(edit_distance("", "run"), 3);
+        assert_eq!(edit_distance("testcase", "tc"), 6);
+    }
+
+    #[test]
+    fn did_you_mean_closest() {
+        let names = ["run", "search", "list"];
+        assert_eq!(did_you_mean("serach", names), Some("search"));
+        assert_eq!(did_you_mean("rnu", names), Some("run"));
+        assert_eq!(did_you_mean("xyz", names), None);
+        assert_eq!(did_you_mean("03s4", ["03s3", "04s1"]), Some("03s3"));
+        assert_eq!(did_you_mean("T", ["t", "h"]), Some("t"));
+        assert_eq!(did_you_mean("x", ["t", "h"]), None);
+    }
+}
@@ -0,0 +1,49 @@
+/// Declarative command tree definition
+/// Defines:
+///   command_tree!: describe commands, options, values and handlers in one literal,
+///                  omitted spec fields take CmdSpec::EMPTY / OpnSepc::EMPTY values.
+///   tree     := name => exec, { cmd_fields } [, options: [ { opn_fields }, ... ]]
+///               [, subcmds: [ ( tree ), ... ]] [, hidden: bool]
+/// Duplicate sub command names and flags are checked when the tree is built, see
+/// Command::assemble.
+/// Sample:
+///   let rtcmd = command_tree!(
+///       "ccc" => help_cmder, { title: "ccc solutions", usage: "ccc run 03s3" },
+///       options: [{ short: "h", long: "help", title: "help information" }],
+///       subcmds: [(
+///           "run" => run_cmder, { vspec: ValueSpec::Maybe, vtype: ValueType::QuestionId },
+///           options: [{ short: "t", long: "testcase", vspec: ValueSpec::Must, repeat: true }],
+///       )],
+///   );
+///
+#[macro_export]
+macro_rules! command_tree {
+    (
+        $name:literal => $exec:expr, { $($field:ident : $value:expr),* $(,)? }
+        $(, options: [ $({ $($ofield:ident : $ovalue:expr),* $(,)? }),* $(,)? ])?
+        $(, subcmds: [ $(( $($sub:tt)* )),* $(,)? ])?
+        $(, hidden: $hidden:expr)?
+        $(,)?
+    ) => {{
+        #[allow(clippy::needless_update)]
+        let spec = $crate::cli::cmd::CmdSpec {
+            name: $name,
+            $($field: $value,)*
+            ..$crate::cli::cmd::CmdSpec::EMPTY
+        };
+        #[allow(clippy::needless_update)]
+        let options = vec![$($(
+            $crate::cli::cmd::OpnSepc {
+                $($ofield: $ovalue,)*
+                ..$crate::cli::cmd::OpnSepc::EMPTY
+            }
+        ),*)?];
+        let subcmds = vec![$($($crate::command_tree!($($sub)*)),*)?];
+        #[allow(unused_mut)]
+        let mut cmd = $crate::cli::cmd::Command::assemble(spec, $exec, options, subcmds);
+        $(if $hidden {
+            cmd.hide();
+        })?
+        cmd
+    }};
+}
@@ -0,0 +1,184 @@
+/// Typed values for command and option tokens
+/// Defines:
+///   ValueType: declared type of a value token, validated by parser.
+///   ArgValue : conversion from a validated token to rust type.
+/// Sample:
+///   ccc run 03s3 --limit 10 --timeout 2s --dir io_files
+///
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Value Type of command or option's value token
+#[derive(Debug, Copy, Clone, Default)]
+pub enum ValueType<'a> {
+    #[default]
+    Text,
+    Int,
+    Path,
+    Choice(&'a [&'a str]),
+    QuestionId,
+    Testcase,
+    Duration,
+}
+
+impl ValueType<'_> {
+    /// make doc string for help making
+    pub fn doc(&self) -> String {
+        match self {
+            ValueType::Text => "VALUE".to_string(),
+            ValueType::Int => "INT".to_string(),
+            ValueType::Path => "PATH".to_string(),
+            ValueType::Choice(choices) => choices.join("|"),
+            ValueType::QuestionId => "QID".to_string(),
+            ValueType::Testcase => "TC".to_string(),
+            ValueType::Duration => "TIME".to_string(),
+        }
+    }
+
+    /// accepted form of the value, for error message
+    pub fn expect(&self) -> String {
+        match self {
+            ValueType::Text => "any text".to_string(),
+            ValueType::Int => "an integer".to_string(),
+            ValueType::Path => "an existing path".to_string(),
+            ValueType::Choice(choices) => format!("one of {}", choices.join(", ")),
+            ValueType::QuestionId => "question id like 03s3".to_string(),
+            ValueType::Testcase => "testcase name".to_string(),
+            ValueType::Duration => "duration like 2s, 500ms".to_string(),
+        }
+    }
+
+    /// Validate value token with self type
+    pub fn check(&self, value: &str) -> bool {
+        match self {
+            ValueType::Text | ValueType::Testcase => true,
+            ValueType::Int => i64::from_arg(value).is_some(),
+            ValueType::Path => Path::new(value).exists(),
+            ValueType::Choice(choices) => choices.contains(&value),
+            ValueType::QuestionId => QuestionId::from_arg(value).is_some(),
+            ValueType::Duration => Duration::from_arg(value).is_some(),
+        }
+    }
+}
+
+/// Convert a value token to typed value
+pub trait ArgValue: Sized {
+    fn from_arg(value: &str) -> Option<Self>;
+}
+
+macro_rules! arg_value_from_str {
+    ($($type:ty),*) => {
+        $(impl ArgValue for $type {
+            fn from_arg(value: &str) -> Option<Self> {
+                value.parse::<$type>().ok()
+            }
+        })*
+    };
+}
+
+arg_value_from_str!(i8, i16, i32, i64, u8, u16, u32, u64, usize, f32, f64, bool, String, PathBuf);
+
+/// Duration token: number with unit ms, s, m, h. bare number is seconds.
+impl ArgValue for Duration {
+    fn from_arg(value: &str) -> Option<Self> {
+        let split = value
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(value.len());
+        let (num, unit) = value.split_at(split);
+        let num = num.parse::<u64>().ok()?;
+        match unit {
+            "ms" => Some(Duration::from_millis(num)),
+            "" | "s" => Some(Duration::from_secs(num)),
+            "m" => Some(Duration::from_secs(num.checked_mul(60)?)),
+            "h" => Some(Duration::from_secs(num.checked_mul(3600)?)),
+            _ => None,
+        }
+    }
+}
+
+/// CCC question id: YY + level(j/s) + num. eg. 03s3
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct QuestionId {
+    pub year: u8,
+    pub senior: bool,
+    pub num: u8,
+}
+
+impl ArgValue for QuestionId {
+    fn from_arg(value: &str) -> Option<Self> {
+        let bytes = value.as_bytes();
+        // year is exactly two digits, "+3s3" parses as 3 but is not a question id
+        if bytes.len() != 4 || !bytes[..2].iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        let senior = match bytes[2].to_ascii_lowercase() {
+            b's' => true,
+            b'j' => false,
+            _ => return None,
+        };
+        Some(QuestionId {
+            year: value[0..2].parse().ok()?,
+            senior,
+            num: value[3..].parse().ok()?,
+        })
+    }
+}
+
+impl Display for QuestionId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let level = if self.senior { 's' } else { 'j' };
+        write!(f, "{:02}{}{}", self.year, level, self.num)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn value_type_check() {
+        assert!(ValueType::Text.check("floor plan"));
+        assert!(ValueType::Int.check("-12"));
+        assert!(!ValueType::Int.check("12a"));
+        assert!(ValueType::Path.check("."));
+        assert!(!ValueType::Path.check("./not/exist/dir"));
+        assert!(ValueType::Choice(&["text", "json"]).check("json"));
+        assert!(!ValueType::Choice(&["text", "json"]).check("xml"));
+        assert!(ValueType::QuestionId.check("03s3"));
+        assert!(ValueType::QuestionId.check("15J4"));
+        assert!(!ValueType::QuestionId.check("03x3"));
+        assert!(!ValueType::QuestionId.check("3s3"));
+        assert!(ValueType::Duration.check("2s"));
+        assert!(!ValueType::Duration.check("2days"));
+    }
+
+    #[test]
+    fn value_type_doc() {
+        assert_eq!(ValueType::Text.doc(), "VALUE");
+        assert_eq!(ValueType::Choice(&["text", "json"]).doc(), "text|json");
+        assert_eq!(
+            ValueType::Choice(&["text", "json"]).expect(),
+            "one of text, json"
+        );
+    }
+
+    #[test]
+    fn arg_value_convert() {
+        assert_eq!(u32::from_arg("10"), Some(10));
+        assert_eq!(u32::from_arg("-1"), None);
+        assert_eq!(
+            Duration::from_arg("500ms"),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(Duration::from_arg("2"), Some(Duration::from_secs(2)));
+        assert_eq!(Duration::from_arg("3m"), Some(Duration::from_secs(180)));
+        assert_eq!(Duration::from_arg(&format!("{}h", u64::MAX / 60)), None);
+        assert_eq!(Duration::from_arg(&format!("{}m", u64::MAX)), None);
+        let qid = QuestionId::from_arg("03S3").unwrap();
+        assert_eq!((qid.year, qid.senior, qid.num), (3, true, 3));
+        assert_eq!(qid.to_string(), "03s3");
+        assert_eq!(QuestionId::from_arg("+3s3"), None);
+        assert_eq!(QuestionId::from_arg("-3s3"), None);
+    }
+}
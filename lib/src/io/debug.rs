@@ -1,12 +1,28 @@
 /// debug tools for ccc
+/// Defines:
+///   DebugLevel : level of a log record, Trace < Debug < Info < Warn < Error.
+///                Answer is a channel of solver answers rather than a level, always visible.
+///   LogFilter  : level threshold per module, parsed from directives like CCC_LOG.
+/// Directives(comma separated):
+///   "info"                   : threshold of all modules.
+///   "solve=debug,io=warn"    : threshold of module solve and io, default threshold otherwise.
+///   a module is a span name, eg. question "03s3" or phase "solve", see Logger::span.
+///   the innermost entered span with a directive decides the threshold.
 ///
 use core::fmt::Display;
+use std::env;
+use std::str::FromStr;
+
+/// env var of log filter directives
+pub const LOG_ENV: &str = "CCC_LOG";
 
 /// DebugLevel enum all informations' level types.
-#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd)]
 #[repr(u8)]
 pub enum DebugLevel {
-    Info = 0,
+    Trace = 0,
+    Debug,
+    Info,
     #[default]
     Warn,
     Error,
@@ -16,6 +32,8 @@ pub enum DebugLevel {
 impl Display for DebugLevel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            DebugLevel::Trace => write!(f, "TRAC"),
+            DebugLevel::Debug => write!(f, "DEBG"),
             DebugLevel::Info => write!(f, "INFO"),
             DebugLevel::Warn => write!(f, "WARN"),
             DebugLevel::Error => write!(f, "ERRO"),
@@ -24,14 +42,94 @@ impl Display for DebugLevel {
     }
 }
 
+/// level name in any case, or its display code
+impl FromStr for DebugLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "trace" | "trac" => Ok(DebugLevel::Trace),
+            "debug" | "debg" => Ok(DebugLevel::Debug),
+            "info" => Ok(DebugLevel::Info),
+            "warn" | "warning" => Ok(DebugLevel::Warn),
+            "error" | "erro" => Ok(DebugLevel::Error),
+            "answer" | "answ" => Ok(DebugLevel::Answer),
+            _ => Err(format!("unknown log level '{}'", s)),
+        }
+    }
+}
+
 impl DebugLevel {
+    /// Whether a record of current_level passes threshold self, answers always pass.
     pub fn can_trigger(&self, current_level: &DebugLevel) -> bool {
-        if (*self as u8) > *current_level as u8 {
-            false
-        } else {
-            true
+        *current_level == DebugLevel::Answer || self <= current_level
+    }
+}
+
+/// Level threshold per module
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct LogFilter {
+    default: DebugLevel,
+    modules: Vec<(String, DebugLevel)>,
+}
+
+impl LogFilter {
+    /// Same threshold for all modules
+    pub fn new(default: DebugLevel) -> LogFilter {
+        LogFilter {
+            default,
+            modules: Vec::new(),
+        }
+    }
+
+    /// Parse directives, a bare level overrides default.
+    pub fn parse(directives: &str, default: DebugLevel) -> Result<LogFilter, String> {
+        let mut filter = LogFilter::new(default);
+        for directive in directives
+            .split(',')
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+        {
+            let invalid = |e: String| format!("invalid log directive '{}': {}", directive, e);
+            match directive.split_once('=') {
+                Some((module, level)) if !module.trim().is_empty() => {
+                    let level = level.trim().parse().map_err(invalid)?;
+                    filter.modules.push((module.trim().to_string(), level));
+                }
+                Some(_) => return Err(invalid("expect module name".to_string())),
+                None => filter.default = directive.parse().map_err(invalid)?,
+            }
+        }
+        Ok(filter)
+    }
+
+    /// Directives from env var CCC_LOG, default if not set.
+    pub fn from_env(default: DebugLevel) -> Result<LogFilter, String> {
+        match env::var(LOG_ENV) {
+            Ok(directives) => LogFilter::parse(&directives, default),
+            Err(_) => Ok(LogFilter::new(default)),
         }
     }
+
+    /// Threshold in entered modules, outermost first
+    pub fn level_of<'a>(&self, modules: impl DoubleEndedIterator<Item = &'a str>) -> DebugLevel {
+        for module in modules.rev() {
+            let matched = self.modules.iter().rev().find(|(name, _)| name == module);
+            if let Some((_, level)) = matched {
+                return *level;
+            }
+        }
+        self.default
+    }
+
+    /// Whether a record of lvl passes in entered modules
+    pub fn can_trigger<'a>(
+        &self,
+        modules: impl DoubleEndedIterator<Item = &'a str>,
+        lvl: &DebugLevel,
+    ) -> bool {
+        self.level_of(modules).can_trigger(lvl)
+    }
 }
 
 #[cfg(test)]
@@ -46,6 +144,8 @@ mod test {
 
     #[test]
     fn debug_level_display() {
+        assert_eq!(DebugLevel::Trace.to_string(), "TRAC");
+        assert_eq!(DebugLevel::Debug.to_string(), "DEBG");
         assert_eq!(DebugLevel::Info.to_string(), "INFO");
         assert_eq!(DebugLevel::Warn.to_string(), "WARN");
         assert_eq!(DebugLevel::Error.to_string(), "ERRO");
@@ -53,9 +153,72 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn debug_level_cmp() {
         let lvl = DebugLevel::Warn;
         assert_eq!(true, lvl.can_trigger(&DebugLevel::Error));
         assert_eq!(false, lvl.can_trigger(&DebugLevel::Info));
     }
+
+    #[test]
+    fn answer_always_triggered() {
+        assert!(DebugLevel::Error.can_trigger(&DebugLevel::Answer));
+        assert!(DebugLevel::Answer.can_trigger(&DebugLevel::Answer));
+        assert!(!DebugLevel::Answer.can_trigger(&DebugLevel::Error));
+        assert!(DebugLevel::Trace.can_trigger(&DebugLevel::Debug));
+    }
+
+    #[test]
+    fn debug_level_parse() {
+        for lvl in [
+            DebugLevel::Trace,
+            DebugLevel::Debug,
+            DebugLevel::Info,
+            DebugLevel::Warn,
+            DebugLevel::Error,
+            DebugLevel::Answer,
+        ] {
+            assert_eq!(lvl.to_string().parse(), Ok(lvl));
+        }
+        assert_eq!("Debug".parse(), Ok(DebugLevel::Debug));
+        assert_eq!("warning".parse(), Ok(DebugLevel::Warn));
+        assert_eq!(
+            "loud".parse::<DebugLevel>(),
+            Err("unknown log level 'loud'".to_string())
+        );
+    }
+
+    #[test]
+    fn log_filter_directives() {
+        let filter = LogFilter::parse("solve=debug, io=warn", DebugLevel::Info).unwrap();
+        assert_eq!(filter.level_of([].into_iter()), DebugLevel::Info);
+        assert_eq!(
+            filter.level_of(["03s3", "solve"].into_iter()),
+            DebugLevel::Debug
+        );
+        assert_eq!(
+            filter.level_of(["solve", "io"].into_iter()),
+            DebugLevel::Warn
+        );
+        assert!(filter.can_trigger(["solve"].into_iter(), &DebugLevel::Debug));
+        assert!(!filter.can_trigger(["io"].into_iter(), &DebugLevel::Info));
+        assert!(filter.can_trigger(["io"].into_iter(), &DebugLevel::Answer));
+
+        let filter = LogFilter::parse("trace,03s3=error", DebugLevel::Info).unwrap();
+        assert_eq!(filter.level_of(["parse"].into_iter()), DebugLevel::Trace);
+        assert_eq!(
+            filter.level_of(["03s3", "parse"].into_iter()),
+            DebugLevel::Error
+        );
+
+        assert_eq!(
+            LogFilter::parse("solve=loud", DebugLevel::Info),
+            Err("invalid log directive 'solve=loud': unknown log level 'loud'".to_string())
+        );
+        assert!(LogFilter::parse("=debug", DebugLevel::Info).is_err());
+        assert_eq!(
+            LogFilter::parse("", DebugLevel::Warn),
+            Ok(LogFilter::default())
+        );
+    }
 }
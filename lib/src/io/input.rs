@@ -1,103 +1,239 @@
-use crate::io::provider::fileio::FileIoReadProvider;
+use crate::io::locator::{LocateError, TcLocator, TcSource};
+use crate::io::scanner::Scanner;
 use crate::io::IoReader;
+use std::fmt::Display;
+use std::path::Path;
 
-const CCC_INPUT_DIR: &str = "/Users/lzcoder/repos/ccc-rust/io_files/";
+/// Malformed testcase input
+///   line: 1-based line number, col: 1-based token index in line, 0 for whole line.
+///   text: offending text, None at end of input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputError {
+    pub path: String,
+    pub line: usize,
+    pub col: usize,
+    pub expect: String,
+    pub text: Option<String>,
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.path, self.line)?;
+        if self.col > 0 {
+            write!(f, ":{}", self.col)?;
+        }
+        match &self.text {
+            Some(text) => write!(f, ": expect {}, found '{}'", self.expect, text),
+            None => write!(f, ": expect {}, found end of input", self.expect),
+        }
+    }
+}
 
 pub struct TcFileInput {
     pub file_handle: Box<dyn IoReader>,
+    file_path: String,
+    line_no: usize, // lines read
 }
 
 /// CCC Testcase File Input
 /// for replacement of user input
 impl TcFileInput {
     /// Load CCC sample input file
-    ///   file_name: sample file in CCC_INPUT_DIR dir. these patterns are cccYYSN[_casename].tc
-    pub fn load_testfile(file_name: &str) -> TcFileInput {
-        let file_path = CCC_INPUT_DIR.to_owned() + file_name;
-        let provider = FileIoReadProvider::open_for_read(&file_path);
+    ///   file_name: sample file in testcase directories or their archives, see TcLocator.
+    ///              these patterns are cccYYSN[_casename].tc
+    pub fn load_testfile(file_name: &str) -> Result<TcFileInput, LocateError> {
+        let source = TcLocator::resolve(None).locate(file_name)?;
+        TcFileInput::open_source(&source)
+    }
+
+    /// Testcase input from any reader, eg. MemIoReadProvider in solver tests
+    ///   name: shown as path in InputError.
+    pub fn new(file_handle: Box<dyn IoReader>, name: &str) -> TcFileInput {
         TcFileInput {
-            file_handle: provider.unwrap(),
+            file_handle,
+            file_path: name.to_string(),
+            line_no: 0,
         }
     }
 
+    /// Open testcase file by path
+    pub fn open(file_path: &Path) -> Result<TcFileInput, LocateError> {
+        TcFileInput::open_source(&TcSource::File(file_path.to_path_buf()))
+    }
+
+    /// Open testcase file or archive member
+    pub fn open_source(source: &TcSource) -> Result<TcFileInput, LocateError> {
+        Ok(TcFileInput::new(source.open()?, &source.to_string()))
+    }
+
+    /// Token scanner over the rest of testcase file
+    pub fn into_scanner(self) -> Scanner {
+        Scanner::new(self.file_handle)
+    }
+
+    /// Error at token col of the last read line
+    pub fn input_error(&self, col: usize, expect: &str, text: Option<&str>) -> InputError {
+        InputError {
+            path: self.file_path.clone(),
+            line: self.line_no,
+            col,
+            expect: expect.to_string(),
+            text: text.map(String::from),
+        }
+    }
+
+    /// Read next line, error with expect at end of input
+    pub fn next_line(&mut self, expect: &str) -> Result<String, InputError> {
+        let line = self.file_handle.read_line();
+        self.line_no += 1;
+        match line {
+            Ok(Some(line)) => Ok(line),
+            Ok(None) => Err(self.input_error(0, expect, None)),
+            Err(e) => Err(self.input_error(0, expect, Some(&format!("read error: {}", e)))),
+        }
+    }
+
+    /// Parse token at col of the last read line
+    fn parse_token<T: std::str::FromStr>(&self, col: usize, token: &str) -> Result<T, InputError> {
+        token
+            .parse::<T>()
+            .map_err(|_| self.input_error(col, type_name::<T>(), Some(token)))
+    }
+
     /// Read int input in a single line.
-    pub fn read_int(&mut self) -> i32 {
-        let r = self.file_handle.read_line().unwrap();
-        let parse_r = r.parse::<i32>();
-        match parse_r {
-            Ok(n) => return n,
-            Err(e) => {
-                println!("{}", e);
-                return 0;
-            }
-        };
+    pub fn read_int(&mut self) -> Result<i32, InputError> {
+        let ln = self.next_line("i32")?;
+        self.parse_token(1, ln.trim())
     }
 
     /// Read String input in a single line.
-    pub fn read_str(&mut self) -> String {
-        self.file_handle.read_line().unwrap()
+    pub fn read_str(&mut self) -> Result<String, InputError> {
+        self.next_line("line")
     }
 
     /// Read vec that input in a single line.
     /// sep_by_space: words seperated by " "(space) or char in String.
-    pub fn read_strvec(&mut self, sep_by_space: bool) -> Vec<String> {
-        let ln = self.file_handle.read_line().unwrap();
+    pub fn read_strvec(&mut self, sep_by_space: bool) -> Result<Vec<String>, InputError> {
+        let ln = self.next_line("line")?;
         if sep_by_space {
-            ln.split_whitespace().map(|s| String::from(s)).collect()
+            Ok(ln.split_whitespace().map(String::from).collect())
         } else {
-            let mut chunks = Vec::new();
-            for c in ln.chars() {
-                let mut cs = c.to_string();
-                chunks.push(std::mem::take(&mut cs));
-            }
-            chunks
+            Ok(ln.chars().map(String::from).collect())
         }
     }
 
     /// Read and type casting T to vec that input in a single line.
-    /// T: std::str::FromStr + Default
-    pub fn read_vec<T: std::str::FromStr + Default>(&mut self) -> Vec<T> {
-        self.read_strvec(true)
+    pub fn read_vec<T: std::str::FromStr>(&mut self) -> Result<Vec<T>, InputError> {
+        self.read_strvec(true)?
             .iter()
-            .map(|s| s.parse::<T>().unwrap_or_default())
+            .enumerate()
+            .map(|(idx, s)| self.parse_token(idx + 1, s))
             .collect()
     }
 }
 
+/// short type name for error message. eg. i32, String
+fn type_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
 /// Read and type casting $type(2nd arg) to tuple that input in a single line.
-/// eg. let (a, b) = read_tuple_t(io_reader, i32);
+/// eg. let (a, b) = read_tuple!(io_reader, i32)?;
 #[allow(unused_macros)]
 macro_rules! read_tuple {
     ($obj:ident, $type:ty) => {
-        $obj.read_vec::<$type>()
-            .into_iter()
-            .collect_tuple()
-            .unwrap()
+        $obj.read_vec::<$type>().and_then(|v| {
+            let found = format!("{} values", v.len());
+            v.into_iter()
+                .collect_tuple()
+                .ok_or_else(|| $obj.input_error(0, "tuple", Some(&found)))
+        })
     };
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::io::provider::memio::MemIoReadProvider;
     use itertools::Itertools;
 
     #[test]
     fn read_file_to_int_and_vec_and_tuple() {
-        let mut input = TcFileInput::load_testfile("test_input.tc");
-        assert_eq!(input.read_int(), 105);
-        assert_eq!(input.read_int(), 14);
-        assert_eq!(input.read_int(), 16);
+        let mut input = TcFileInput::load_testfile("test_input.tc").unwrap();
+        assert_eq!(input.read_int(), Ok(105));
+        assert_eq!(input.read_int(), Ok(14));
+        assert_eq!(input.read_int(), Ok(16));
         assert_eq!(
-            input.read_strvec(false),
+            input.read_strvec(false).unwrap(),
             vec!["I", "I", "I", "I", "I", "I", "I", "I", "I", "I", "I", "I", "I", "I", "I", "I"]
         );
         assert_eq!(
-            input.read_strvec(false),
+            input.read_strvec(false).unwrap(),
             vec!["I", ".", ".", ".", ".", ".", ".", "I", ".", ".", ".", ".", ".", ".", ".", "I"]
         );
-        assert_eq!(input.read_strvec(true), vec!["a", "b", "c", "xyz"]);
-        assert_eq!(input.read_vec::<i16>(), vec![1, 2, 3]);
-        let (a, b, c) = read_tuple!(input, i16);
+        assert_eq!(input.read_strvec(true).unwrap(), vec!["a", "b", "c", "xyz"]);
+        assert_eq!(input.read_vec::<i16>(), Ok(vec![1, 2, 3]));
+        let (a, b, c) = read_tuple!(input, i16).unwrap();
         assert_eq!((a, b, c), (1, 2, 3));
     }
+
+    #[test]
+    fn read_malformed_input() {
+        let mut input = TcFileInput::load_testfile("test_input.tc").unwrap();
+        assert_eq!(
+            input.read_vec::<bool>(),
+            Err(input.input_error(1, "bool", Some("105")))
+        );
+        input.read_str().unwrap();
+        input.read_str().unwrap();
+        let err = input.read_int().unwrap_err();
+        assert_eq!((err.line, err.col), (4, 1));
+        assert!(err
+            .to_string()
+            .ends_with("test_input.tc:4:1: expect i32, found 'IIIIIIIIIIIIIIII'"));
+        input.read_str().unwrap();
+        let err = input.read_vec::<char>().unwrap_err();
+        assert_eq!((err.line, err.col), (6, 4));
+        assert_eq!(err.text.as_deref(), Some("xyz"));
+        let err = read_tuple!(input, i16)
+            .map(|(a, b): (i16, i16)| a + b)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .ends_with(":7: expect tuple, found '3 values'"));
+        input.read_str().unwrap();
+        let err = input.read_int().unwrap_err();
+        assert!(err
+            .to_string()
+            .ends_with(":9: expect i32, found end of input"));
+    }
+
+    #[test]
+    fn read_file_by_scanner() {
+        let mut sc = TcFileInput::load_testfile("ccc2003s3.tc")
+            .unwrap()
+            .into_scanner();
+        assert_eq!(sc.next_n::<usize>(3).unwrap(), Some(vec![105, 14, 16]));
+        let plan = sc.next_grid(14, 16).unwrap().unwrap();
+        assert_eq!(plan[1].iter().collect::<String>(), "I......I.......I");
+        assert_eq!(plan[13], vec!['I'; 16]);
+    }
+
+    #[test]
+    fn read_from_memory() {
+        let reader = MemIoReadProvider::from_text("2\n1 x\n");
+        let mut input = TcFileInput::new(reader, "<mem>");
+        assert_eq!(input.read_int(), Ok(2));
+        assert_eq!(
+            input.read_vec::<i32>().unwrap_err().to_string(),
+            "<mem>:2:2: expect i32, found 'x'"
+        );
+    }
+
+    #[test]
+    fn load_missing_testfile() {
+        let err = TcFileInput::load_testfile("ccc99s9.tc").err().unwrap();
+        assert!(matches!(err, LocateError::NotFound(name, _) if name == "ccc99s9.tc"));
+    }
 }
@@ -0,0 +1,449 @@
+/// CCC Testcase File Locator
+/// Defines:
+///   roots   : testcase directories seeked in order,
+///             1. --dir option of CLI
+///             2. $CCC_TESTCASE_DIR
+///             3. io_files in workspace root of current directory
+///             4. io_files in workspace root of this crate
+///   tc file : cccYYSN[_casename].tc, YY is 2 or 4 digits year, S is s(senior) or j(junior).
+///   archive : .zip or .tar.gz in roots, its members are testcases too,
+///             tc file member by its file name, or *.in member of archive named cccYYSN[_casename].
+/// Sample:
+///   ccc2003s3.tc          => 03s3
+///   ccc03s3_rooms.tc      => 03s3, case "rooms"
+///   ccc03s3.zip!data/1.in => 03s3, case "1", expected answer in data/1.out
+///
+use crate::cli::value::QuestionId;
+use crate::io::provider::archio::{ArchIoReadProvider, ArchiveKind};
+use crate::io::provider::fileio::FileIoReadProvider;
+use crate::io::IoReader;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+/// env var of testcase directory
+pub const TESTCASE_DIR_ENV: &str = "CCC_TESTCASE_DIR";
+
+/// testcase directory name in workspace root
+pub const TESTCASE_DIR: &str = "io_files";
+
+/// Testcase file name in cccYYSN[_casename].tc
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TcName {
+    pub qid: QuestionId,
+    pub case: Option<String>,
+}
+
+impl TcName {
+    /// Parse testcase file name, None if not in naming convention
+    pub fn parse(file_name: &str) -> Option<TcName> {
+        let stem = file_name.strip_prefix("ccc")?.strip_suffix(".tc")?;
+        let (id, case) = match stem.split_once('_') {
+            Some((id, case)) if !case.is_empty() => (id, Some(case.to_string())),
+            Some(_) => return None,
+            None => (stem, None),
+        };
+        let level = id.find(|c: char| c.is_ascii_alphabetic())?;
+        let (year, rest) = id.split_at(level);
+        if !(year.len() == 2 || year.len() == 4) || !year.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let senior = match rest.as_bytes()[0].to_ascii_lowercase() {
+            b's' => true,
+            b'j' => false,
+            _ => return None,
+        };
+        let qid = QuestionId {
+            year: (year.parse::<u16>().ok()? % 100) as u8,
+            senior,
+            num: rest[1..].parse().ok()?,
+        };
+        Some(TcName { qid, case })
+    }
+}
+
+impl Display for TcName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ccc{}", self.qid)?;
+        if let Some(case) = &self.case {
+            write!(f, "_{}", case)?;
+        }
+        write!(f, ".tc")
+    }
+}
+
+/// Testcase source, a file or a member of archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TcSource {
+    File(PathBuf),
+    Member(PathBuf, String), // archive path, member name
+}
+
+impl Display for TcSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TcSource::File(path) => write!(f, "{}", path.display()),
+            TcSource::Member(archive, member) => write!(f, "{}!{}", archive.display(), member),
+        }
+    }
+}
+
+impl TcSource {
+    /// File name of source, without directories of archive member
+    pub fn file_name(&self) -> Option<&str> {
+        match self {
+            TcSource::File(path) => path.file_name()?.to_str(),
+            TcSource::Member(_, member) => member.rsplit('/').next(),
+        }
+    }
+
+    /// Testcase name of source, None if not a testcase
+    pub fn tc_name(&self) -> Option<TcName> {
+        if let Some(name) = self.file_name().and_then(TcName::parse) {
+            return Some(name);
+        }
+        let TcSource::Member(archive, _) = self else {
+            return None;
+        };
+        let stem = self.file_name()?.strip_suffix(".in")?;
+        let mut name = TcName::parse(&format!("{}.tc", ArchiveKind::stem(archive)?))?;
+        name.case = match name.case {
+            Some(case) => Some(format!("{}_{}", case, stem)),
+            None => Some(stem.to_string()),
+        };
+        Some(name)
+    }
+
+    /// Line reader of testcase input
+    pub fn open(&self) -> Result<Box<dyn IoReader>, LocateError> {
+        match self {
+            TcSource::File(path) => FileIoReadProvider::open_for_read(&path.to_string_lossy())
+                .map_err(|e| LocateError::Unreadable(path.clone(), e.to_string())),
+            TcSource::Member(archive, member) => ArchIoReadProvider::open_member(archive, member)
+                .map_err(|e| LocateError::Unreadable(archive.clone(), e.to_string())),
+        }
+    }
+
+    /// Expected answer lines in *.out member paired with *.in member, None if absent
+    pub fn answer(&self) -> Option<Vec<String>> {
+        let TcSource::Member(archive, member) = self else {
+            return None;
+        };
+        let out = format!("{}.out", member.strip_suffix(".in")?);
+        let bytes = ArchIoReadProvider::read_member(archive, &out).ok()?;
+        let text = String::from_utf8_lossy(&bytes);
+        Some(text.lines().map(|l| l.trim_end().to_string()).collect())
+    }
+}
+
+/// Testcase locate error
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LocateError {
+    NoRoot,                         // none of testcase directories exists
+    NotFound(String, Vec<PathBuf>), // file name, seeked roots
+    Unreadable(PathBuf, String),    // file path, reason
+}
+
+impl Display for LocateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LocateError::NoRoot => write!(
+                f,
+                "no testcase directory, use --dir, ${} or {} in workspace root",
+                TESTCASE_DIR_ENV, TESTCASE_DIR
+            ),
+            LocateError::NotFound(name, roots) => {
+                let roots: Vec<String> = roots.iter().map(|r| r.display().to_string()).collect();
+                write!(f, "testcase '{}' not found in: {}", name, roots.join(", "))
+            }
+            LocateError::Unreadable(path, reason) => {
+                write!(f, "can not read testcase '{}': {}", path.display(), reason)
+            }
+        }
+    }
+}
+
+/// Testcase directories seeked in order
+#[derive(Debug, Clone, Default)]
+pub struct TcLocator {
+    roots: Vec<PathBuf>,
+}
+
+impl TcLocator {
+    /// Locator with default roots, cli_dir is --dir option's value
+    ///   roots not existing are skipped.
+    pub fn resolve(cli_dir: Option<PathBuf>) -> TcLocator {
+        let mut locator = TcLocator::default();
+        if let Some(dir) = cli_dir {
+            locator.add_root(dir);
+        }
+        if let Some(dir) = env::var_os(TESTCASE_DIR_ENV).filter(|v| !v.is_empty()) {
+            locator.add_root(PathBuf::from(dir));
+        }
+        let bases = [
+            env::current_dir().ok(),
+            Some(PathBuf::from(env!("CARGO_MANIFEST_DIR"))),
+        ];
+        for base in bases.iter().flatten() {
+            if let Some(root) = TcLocator::workspace_root(base) {
+                locator.add_root(root.join(TESTCASE_DIR));
+            }
+        }
+        locator
+    }
+
+    /// Seek the workspace root(Cargo.toml with [workspace]) from dir up to file system root
+    pub fn workspace_root(dir: &Path) -> Option<PathBuf> {
+        dir.ancestors()
+            .find(|d| {
+                fs::read_to_string(d.join("Cargo.toml"))
+                    .map(|toml| toml.contains("[workspace]"))
+                    .unwrap_or(false)
+            })
+            .map(Path::to_path_buf)
+    }
+
+    /// Add a testcase directory after existing ones, skip duplicated or not existing one
+    pub fn add_root(&mut self, dir: PathBuf) -> &mut TcLocator {
+        if dir.is_dir() && !self.roots.contains(&dir) {
+            self.roots.push(dir);
+        }
+        self
+    }
+
+    /// testcase directories in seek order
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// Locate a testcase by file name in the first root having it.
+    ///   files in root are seeked before archive members.
+    pub fn locate(&self, file_name: &str) -> Result<TcSource, LocateError> {
+        if self.roots.is_empty() {
+            return Err(LocateError::NoRoot);
+        }
+        for root in &self.roots {
+            let path = root.join(file_name);
+            if path.is_file() {
+                return Ok(TcSource::File(path));
+            }
+            let member = TcLocator::sources(root)
+                .into_iter()
+                .find(|s| matches!(s, TcSource::Member(..)) && s.file_name() == Some(file_name));
+            if let Some(member) = member {
+                return Ok(member);
+            }
+        }
+        Err(LocateError::NotFound(
+            file_name.to_string(),
+            self.roots.clone(),
+        ))
+    }
+
+    /// Files in root sorted by file name, then members of archives in root.
+    ///   files come first as in locate, so a file shadows a member of the same name.
+    ///   unreadable archives are skipped.
+    pub fn sources(root: &Path) -> Vec<TcSource> {
+        let Ok(entries) = fs::read_dir(root) else {
+            return Vec::new();
+        };
+        let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+        paths.sort();
+        let (archives, files): (Vec<PathBuf>, Vec<PathBuf>) = paths
+            .into_iter()
+            .filter(|p| p.is_file())
+            .partition(|p| ArchiveKind::of(p).is_some());
+        let mut sources: Vec<TcSource> = files.into_iter().map(TcSource::File).collect();
+        for archive in archives {
+            let members = ArchIoReadProvider::members(&archive).unwrap_or_default();
+            for member in members {
+                sources.push(TcSource::Member(archive.clone(), member));
+            }
+        }
+        sources
+    }
+
+    /// All testcases of question, sorted by case name.
+    ///   same testcase name is shadowed as in locate: by earlier roots, and by files in
+    ///   the same root over archive members.
+    pub fn cases(&self, qid: &QuestionId) -> Vec<(TcName, TcSource)> {
+        let mut cases: Vec<(TcName, TcSource)> = Vec::new();
+        for root in &self.roots {
+            for source in TcLocator::sources(root) {
+                let Some(name) = source.tc_name() else {
+                    continue;
+                };
+                if name.qid == *qid && !cases.iter().any(|(n, _)| *n == name) {
+                    cases.push((name, source));
+                }
+            }
+        }
+        cases.sort_by(|(a, _), (b, _)| a.case.cmp(&b.case));
+        cases
+    }
+
+    /// Locate testcase file of question, case None is the file without case name.
+    pub fn locate_case(
+        &self,
+        qid: &QuestionId,
+        case: Option<&str>,
+    ) -> Result<TcSource, LocateError> {
+        if self.roots.is_empty() {
+            return Err(LocateError::NoRoot);
+        }
+        let name = TcName {
+            qid: *qid,
+            case: case.map(String::from),
+        };
+        self.cases(qid)
+            .into_iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, source)| source)
+            .ok_or_else(|| LocateError::NotFound(name.to_string(), self.roots.clone()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io::provider::archio::test::make_archive;
+
+    fn qid(year: u8, senior: bool, num: u8) -> QuestionId {
+        QuestionId { year, senior, num }
+    }
+
+    #[test]
+    fn tc_name_parse() {
+        let name = TcName::parse("ccc2003s3.tc").unwrap();
+        assert_eq!((name.qid, name.case), (qid(3, true, 3), None));
+        let name = TcName::parse("ccc03j5_rooms.tc").unwrap();
+        assert_eq!(name.qid, qid(3, false, 5));
+        assert_eq!(name.case.as_deref(), Some("rooms"));
+        assert_eq!(name.to_string(), "ccc03j5_rooms.tc");
+        assert_eq!(TcName::parse("test_input.tc"), None);
+        assert_eq!(TcName::parse("ccc203s3.tc"), None);
+        assert_eq!(TcName::parse("ccc03x3.tc"), None);
+        assert_eq!(TcName::parse("ccc03s3_.tc"), None);
+        assert_eq!(TcName::parse("ccc03s3.txt"), None);
+    }
+
+    #[test]
+    fn locate_in_roots() {
+        let base = env::temp_dir().join(format!("ccc_locator_test_{}", std::process::id()));
+        let (first, second) = (base.join("first"), base.join("second"));
+        fs::create_dir_all(&first).unwrap();
+        fs::create_dir_all(&second).unwrap();
+        fs::write(first.join("ccc03s3.tc"), "1").unwrap();
+        fs::write(second.join("ccc03s3.tc"), "2").unwrap();
+        fs::write(second.join("ccc2003s3_big.tc"), "3").unwrap();
+
+        let mut locator = TcLocator::default();
+        assert_eq!(locator.locate("ccc03s3.tc"), Err(LocateError::NoRoot));
+        locator
+            .add_root(first.clone())
+            .add_root(second.clone())
+            .add_root(base.join("absent"));
+        assert_eq!(locator.roots(), [first.clone(), second.clone()]);
+        assert_eq!(
+            locator.locate("ccc03s3.tc"),
+            Ok(TcSource::File(first.join("ccc03s3.tc")))
+        );
+        assert_eq!(
+            locator.locate("ccc03s4.tc").unwrap_err().to_string(),
+            format!(
+                "testcase 'ccc03s4.tc' not found in: {}, {}",
+                first.display(),
+                second.display()
+            )
+        );
+
+        let cases = locator.cases(&qid(3, true, 3));
+        let sources: Vec<String> = cases.iter().map(|(_, s)| s.to_string()).collect();
+        assert_eq!(
+            sources,
+            [
+                first.join("ccc03s3.tc").display().to_string(),
+                second.join("ccc2003s3_big.tc").display().to_string()
+            ]
+        );
+        assert_eq!(
+            locator.locate_case(&qid(3, true, 3), Some("big")),
+            Ok(TcSource::File(second.join("ccc2003s3_big.tc")))
+        );
+        assert!(locator
+            .locate_case(&qid(3, true, 3), Some("small"))
+            .is_err());
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn locate_in_archives() {
+        let root = env::temp_dir().join(format!("ccc_locator_archive_{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        make_archive(
+            &root.join("ccc03s3.zip"),
+            &[
+                ("data/1.in", "1"),
+                ("data/1.out", "4 rooms"),
+                ("ccc03s3_x.tc", "2"),
+            ],
+        );
+        make_archive(
+            &root.join("ccc2003s3_official.tar.gz"),
+            &[("2.in", "3"), ("notes.txt", "")],
+        );
+        fs::write(root.join("ccc03s3_x.tc"), "4").unwrap();
+
+        let mut locator = TcLocator::default();
+        locator.add_root(root.clone());
+        let member = |archive: &str, name: &str| TcSource::Member(root.join(archive), name.into());
+        let cases: Vec<(Option<String>, TcSource)> = locator
+            .cases(&qid(3, true, 3))
+            .into_iter()
+            .map(|(n, s)| (n.case, s))
+            .collect();
+        assert_eq!(
+            cases,
+            [
+                (Some("1".into()), member("ccc03s3.zip", "data/1.in")),
+                (
+                    Some("official_2".into()),
+                    member("ccc2003s3_official.tar.gz", "2.in")
+                ),
+                (Some("x".into()), TcSource::File(root.join("ccc03s3_x.tc"))),
+            ]
+        );
+        assert_eq!(
+            locator.locate("ccc03s3_x.tc"),
+            Ok(TcSource::File(root.join("ccc03s3_x.tc")))
+        );
+        let source = locator.locate("1.in").unwrap();
+        assert_eq!(
+            source.to_string(),
+            format!("{}!data/1.in", root.join("ccc03s3.zip").display())
+        );
+        assert_eq!(source.answer(), Some(vec!["4 rooms".to_string()]));
+        assert_eq!(
+            source.open().unwrap().read_line().unwrap().as_deref(),
+            Some("1")
+        );
+        let source = locator
+            .locate_case(&qid(3, true, 3), Some("official_2"))
+            .unwrap();
+        assert_eq!(source.answer(), None);
+        assert_eq!(
+            source.open().unwrap().read_line().unwrap().as_deref(),
+            Some("3")
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn resolve_workspace_root() {
+        let root = TcLocator::workspace_root(Path::new(env!("CARGO_MANIFEST_DIR"))).unwrap();
+        assert!(root.join("lib").is_dir());
+        let locator = TcLocator::resolve(None);
+        assert!(locator.roots().contains(&root.join(TESTCASE_DIR)));
+        assert!(locator.locate("test_input.tc").is_ok());
+    }
+}
@@ -1,40 +1,212 @@
-use colored::Colorize;
-
-use crate::io::debug::DebugLevel;
-use crate::io::provider::stdio;
+use crate::io::debug::{DebugLevel, LogFilter};
+use crate::io::markup;
+use crate::io::record::{LogFormat, LogRecord};
+use crate::io::sink::LogSink;
 use crate::io::IoWriter;
-// use colored::Colorize;
+use std::ops::{Deref, DerefMut};
+use std::time::{Instant, SystemTime};
 
+/// Logger of solvers, messages may contain [[...]] markup, see io::markup.
+///   a message passes the logger filter first, see io::debug::LogFilter,
+///   then goes to every sink whose level range has it, in format of the sink.
+/// Spans(scoped context):
+///   messages in spans are indented and prefixed with span path, except answers.
+///   a span logs its elapsed time on exit, when its guard is dropped.
+///   let mut case = logger.testcase_span("s3.1");
+///   let mut parse = case.span("parse");
+///   parse.info("rows [[14]]");   // [INF]=>    [s3.1/parse] rows 14
+///   drop(parse);                 // [INF]=>    [s3.1/parse] end elapsed_ms=0.012
 pub struct Logger {
-    output_handle: Box<dyn IoWriter>,
-    trigger_level: DebugLevel,
+    sinks: Vec<LogSink>,
+    filter: LogFilter,
+    question: Option<String>,
+    testcase: Option<String>,
+    spans: Vec<Span>,
+}
+
+/// An entered span, with context to restore on exit
+struct Span {
+    name: String,
+    started: Instant,
+    question: Option<String>,
+    testcase: Option<String>,
+}
+
+/// Guard of an entered span, exits the span when dropped.
+///   derefs to Logger, so messages and nested spans go through it.
+pub struct SpanGuard<'a> {
+    logger: &'a mut Logger,
+}
+
+impl Deref for SpanGuard<'_> {
+    type Target = Logger;
+
+    fn deref(&self) -> &Logger {
+        self.logger
+    }
+}
+
+impl DerefMut for SpanGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Logger {
+        self.logger
+    }
+}
+
+impl Drop for SpanGuard<'_> {
+    fn drop(&mut self) {
+        self.logger.exit_span();
+    }
 }
 
 impl Logger {
+    /// Logger to stdout, colored unless redirected or NO_COLOR is set
     pub fn create_logger(trigger_level: DebugLevel) -> Logger {
-        let output_handle = stdio::StdIoProvider::new_writer(true).unwrap();
+        Logger::with_sinks(vec![LogSink::stdout(DebugLevel::Trace)])
+            .with_filter(LogFilter::new(trigger_level))
+    }
+    /// Logger writing to any writer, eg. MemIoWriteProvider in tests, plain text
+    pub fn new(output_handle: Box<dyn IoWriter>, trigger_level: DebugLevel) -> Logger {
+        Logger::with_sinks(vec![LogSink::new(output_handle, DebugLevel::Trace)])
+            .with_filter(LogFilter::new(trigger_level))
+    }
+    /// Logger to several destinations, see io::sink, levels are filtered by sinks only
+    pub fn with_sinks(sinks: Vec<LogSink>) -> Logger {
         Logger {
-            output_handle,
-            trigger_level,
+            sinks,
+            filter: LogFilter::new(DebugLevel::Trace),
+            question: None,
+            testcase: None,
+            spans: Vec::new(),
         }
     }
-    fn format_log(lvl: DebugLevel, msg: &str) -> String {
+    /// Filter of all messages, eg. LogFilter::from_env
+    pub fn with_filter(mut self, filter: LogFilter) -> Logger {
+        self.filter = filter;
+        self
+    }
+    pub fn add_sink(&mut self, sink: LogSink) {
+        self.sinks.push(sink);
+    }
+    /// Question of following records, for JSON lines
+    pub fn set_question(&mut self, question: Option<&str>) {
+        self.question = question.map(String::from);
+    }
+    /// Testcase of following records, for JSON lines
+    pub fn set_testcase(&mut self, testcase: Option<&str>) {
+        self.testcase = testcase.map(String::from);
+    }
+    /// Enter a phase span, eg. "parse" or "solve"
+    pub fn span(&mut self, name: &str) -> SpanGuard<'_> {
+        self.spans.push(Span {
+            name: name.to_string(),
+            started: Instant::now(),
+            question: self.question.clone(),
+            testcase: self.testcase.clone(),
+        });
+        SpanGuard { logger: self }
+    }
+    /// Enter a span of question, which is the question of records in it
+    pub fn question_span(&mut self, question: &str) -> SpanGuard<'_> {
+        let mut guard = self.span(question);
+        guard.set_question(Some(question));
+        guard
+    }
+    /// Enter a span of testcase, which is the testcase of records in it
+    pub fn testcase_span(&mut self, testcase: &str) -> SpanGuard<'_> {
+        let mut guard = self.span(testcase);
+        guard.set_testcase(Some(testcase));
+        guard
+    }
+    fn exit_span(&mut self) {
+        let Some(span) = self.spans.last() else {
+            return;
+        };
+        let elapsed = format!("{:.3}", span.started.elapsed().as_secs_f64() * 1000.0);
+        self.info_kv("end", &[("elapsed_ms", &elapsed)]);
+        let span = self.spans.pop().unwrap();
+        self.question = span.question;
+        self.testcase = span.testcase;
+    }
+    /// Names of entered spans, outermost first
+    fn span_path(&self) -> Option<String> {
+        let names: Vec<&str> = self.spans.iter().map(|span| span.name.as_str()).collect();
+        (!names.is_empty()).then(|| names.join("/"))
+    }
+    /// Text line, fields are appended as " key=value"
+    fn format_log(record: &LogRecord, colored: bool) -> String {
+        let mut msg = markup::render(record.msg, colored);
+        for (key, value) in record.fields {
+            msg.push_str(&format!(" {}={}", key, value));
+        }
+        let lvl = record.level;
+        if let (Some(span), false) = (record.span, lvl == DebugLevel::Answer) {
+            let indent = "  ".repeat(span.split('/').count());
+            let prefix = format!("[{}]", span);
+            let prefix = match colored {
+                true => markup::paint(&prefix, "2"),
+                false => prefix,
+            };
+            msg = format!("{}{} {}", indent, prefix, msg);
+        }
+        if !colored {
+            return match lvl {
+                DebugLevel::Trace => format!("[TRC]=>{}", msg),
+                DebugLevel::Debug => format!("[DBG]=>{}", msg),
+                DebugLevel::Info => format!("[INF]=>{}", msg),
+                DebugLevel::Warn => format!("[WAR]=>{}", msg),
+                DebugLevel::Error => format!("[ERR]=>{}", msg),
+                DebugLevel::Answer => msg,
+            };
+        }
+        // escapes are written by sink's color decision, not colored's stdout detection
         match lvl {
-            DebugLevel::Info => format!("{}{}", "[INF]=>", Logger::format_msg(msg)),
-            DebugLevel::Warn => format!("{}{}", "[WAR]=>".yellow().bold(), Logger::format_msg(msg)),
-            DebugLevel::Error => format!("{}{}", "[ERR]=>".red().bold(), Logger::format_msg(msg)),
-            DebugLevel::Answer => format!("{}", Logger::format_msg(msg).black().on_blue()),
+            DebugLevel::Trace => format!("{}{}", markup::paint("[TRC]=>", "2"), msg),
+            DebugLevel::Debug => format!("{}{}", markup::paint("[DBG]=>", "34"), msg),
+            DebugLevel::Info => format!("{}{}", "[INF]=>", msg),
+            DebugLevel::Warn => format!("{}{}", markup::paint("[WAR]=>", "1;33"), msg),
+            DebugLevel::Error => format!("{}{}", markup::paint("[ERR]=>", "1;31"), msg),
+            DebugLevel::Answer => markup::paint(&msg, "30;44"),
         }
     }
-    fn format_msg(msg: &str) -> String {
-        // TODO: replace [[]] to colored string.
-        msg.to_string()
-    }
     pub fn log(&mut self, lvl: DebugLevel, msg: &str) {
-        if self.trigger_level.can_trigger(&lvl) {
-            let formatted_msg = Logger::format_log(lvl, msg);
-            self.output_handle.write_line(formatted_msg.as_str());
+        self.log_kv(lvl, msg, &[])
+    }
+    /// Log with key/value fields
+    pub fn log_kv(&mut self, lvl: DebugLevel, msg: &str, fields: &[(&str, &str)]) {
+        let modules = self.spans.iter().map(|span| span.name.as_str());
+        if !self.filter.can_trigger(modules, &lvl) {
+            return;
         }
+        let span = self.span_path();
+        let record = LogRecord {
+            ts: SystemTime::now(),
+            level: lvl,
+            question: self.question.as_deref(),
+            testcase: self.testcase.as_deref(),
+            span: span.as_deref(),
+            msg,
+            fields,
+        };
+        for sink in self.sinks.iter_mut().filter(|sink| sink.can_trigger(&lvl)) {
+            let formatted_msg = match sink.format() {
+                LogFormat::Text => Logger::format_log(&record, sink.colored()),
+                LogFormat::Json => record.to_json(),
+            };
+            // log is best effort, a failed write does not fail the solver.
+            let _ = sink.write_line(formatted_msg.as_str());
+        }
+    }
+    /// Flush buffered lines of all sinks, best effort too.
+    pub fn flush(&mut self) {
+        for sink in self.sinks.iter_mut() {
+            let _ = sink.flush();
+        }
+    }
+    pub fn trace(&mut self, msg: &str) {
+        self.log(DebugLevel::Trace, msg)
+    }
+    pub fn debug(&mut self, msg: &str) {
+        self.log(DebugLevel::Debug, msg)
     }
     pub fn info(&mut self, msg: &str) {
         self.log(DebugLevel::Info, msg)
@@ -48,6 +220,15 @@ impl Logger {
     pub fn answer(&mut self, msg: &str) {
         self.log(DebugLevel::Answer, msg)
     }
+    pub fn info_kv(&mut self, msg: &str, fields: &[(&str, &str)]) {
+        self.log_kv(DebugLevel::Info, msg, fields)
+    }
+    pub fn warn_kv(&mut self, msg: &str, fields: &[(&str, &str)]) {
+        self.log_kv(DebugLevel::Warn, msg, fields)
+    }
+    pub fn err_kv(&mut self, msg: &str, fields: &[(&str, &str)]) {
+        self.log_kv(DebugLevel::Error, msg, fields)
+    }
 }
 
 #[cfg(test)]
@@ -55,6 +236,8 @@ mod test {
     use colored::Colorize;
 
     use super::*;
+    use crate::io::provider::memio::{LineBuffer, MemIoWriteProvider};
+    use crate::io::sink::ColorPolicy;
 
     #[test]
     fn colorize_output() {
@@ -78,4 +261,177 @@ mod test {
         logger.err("this is error.");
         logger.answer("this is answer.");
     }
+
+    #[test]
+    fn log_to_memory() {
+        let buffer = LineBuffer::default();
+        let mut logger = Logger::new(MemIoWriteProvider::new_writer(&buffer), DebugLevel::Warn);
+        logger.info("hidden information.");
+        logger.warn("shown warning.");
+        logger.err("shown error.");
+        let lines = buffer.borrow();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("shown warning."));
+        assert!(lines[1].ends_with("shown error."));
+
+        let buffer = LineBuffer::default();
+        let mut logger = Logger::new(MemIoWriteProvider::new_writer(&buffer), DebugLevel::Info);
+        logger.info("information.");
+        assert_eq!(*buffer.borrow(), ["[INF]=>information."]);
+    }
+
+    #[test]
+    fn log_markup_plain_and_colored() {
+        let buffer = LineBuffer::default();
+        let mut logger = Logger::new(MemIoWriteProvider::new_writer(&buffer), DebugLevel::Info);
+        logger.warn("wood [[105]] left, [[!no room]] [[?check plan]]");
+        logger.answer("rooms: [[3]]");
+        assert_eq!(
+            *buffer.borrow(),
+            ["[WAR]=>wood 105 left, no room check plan", "rooms: 3"]
+        );
+
+        let buffer = LineBuffer::default();
+        let sink = LogSink::memory(&buffer, DebugLevel::Info).with_color(ColorPolicy::Always);
+        let mut logger = Logger::with_sinks(vec![sink]);
+        logger.err("[[!bad]] row [[3]]");
+        logger.answer("rooms: [[3]]");
+        assert_eq!(
+            *buffer.borrow(),
+            [
+                "\x1b[1;31m[ERR]=>\x1b[0m\x1b[1;31mbad\x1b[0m row \x1b[1;36m3\x1b[0m",
+                "\x1b[30;44mrooms: \x1b[1;36m3\x1b[0m\x1b[0m",
+            ]
+        );
+    }
+
+    #[test]
+    fn log_to_sinks_by_level() {
+        let (logs, answers) = (LineBuffer::default(), LineBuffer::default());
+        let mut logger = Logger::with_sinks(vec![
+            LogSink::memory(&logs, DebugLevel::Info).up_to(DebugLevel::Error),
+            LogSink::memory(&answers, DebugLevel::Answer),
+        ]);
+        logger.info("testcase [[s3.1]]");
+        logger.answer("3");
+        logger.warn("slow");
+        logger.add_sink(LogSink::memory(&answers, DebugLevel::Error));
+        logger.err("failed");
+        logger.flush();
+        assert_eq!(
+            *logs.borrow(),
+            ["[INF]=>testcase s3.1", "[WAR]=>slow", "[ERR]=>failed"]
+        );
+        assert_eq!(*answers.borrow(), ["3", "[ERR]=>failed"]);
+    }
+
+    #[test]
+    fn log_json_lines() {
+        let (text, json) = (LineBuffer::default(), LineBuffer::default());
+        let mut logger = Logger::with_sinks(vec![
+            LogSink::memory(&text, DebugLevel::Info),
+            LogSink::memory(&json, DebugLevel::Info).with_format(LogFormat::Json),
+        ]);
+        logger.set_question(Some("03s3"));
+        logger.info_kv("room [[found]]", &[("size", "12")]);
+        logger.set_testcase(Some("ccc2003s3.tc"));
+        logger.answer("4 rooms");
+        assert_eq!(*text.borrow(), ["[INF]=>room found size=12", "4 rooms"]);
+        let json = json.borrow();
+        assert!(json[0].starts_with(r#"{"ts":"#));
+        assert!(json[0].ends_with(concat!(
+            r#""level":"INFO","question":"03s3","testcase":null,"span":null,"#,
+            r#""msg":"room found","fields":{"size":"12"}}"#
+        )));
+        assert!(json[1].ends_with(concat!(
+            r#""level":"ANSW","question":"03s3","testcase":"ccc2003s3.tc","#,
+            r#""span":null,"msg":"4 rooms","fields":{}}"#
+        )));
+    }
+
+    /// solver returning early with spans entered
+    fn parse_rows(logger: &mut Logger, rows: i32) -> Option<i32> {
+        let mut parse = logger.span("parse");
+        if rows <= 0 {
+            parse.err("bad rows [[0]]");
+            return None;
+        }
+        parse.info("rows ok");
+        Some(rows)
+    }
+
+    #[test]
+    fn log_in_nested_spans() {
+        let (text, json) = (LineBuffer::default(), LineBuffer::default());
+        let mut logger = Logger::with_sinks(vec![
+            LogSink::memory(&text, DebugLevel::Info),
+            LogSink::memory(&json, DebugLevel::Info).with_format(LogFormat::Json),
+        ]);
+        {
+            let mut question = logger.question_span("03s3");
+            let mut case = question.testcase_span("s3.1");
+            assert_eq!(parse_rows(&mut case, 0), None);
+            case.answer("4 rooms");
+            let mut solve = case.span("solve");
+            solve.warn("slow");
+        }
+        logger.info("done");
+        let text = text.borrow();
+        let text: Vec<String> = text
+            .iter()
+            .map(|line| match line.split_once(" elapsed_ms=") {
+                Some((head, _)) => head.to_string() + " elapsed_ms=_",
+                None => line.clone(),
+            })
+            .collect();
+        assert_eq!(
+            text,
+            [
+                "[ERR]=>      [03s3/s3.1/parse] bad rows 0",
+                "[INF]=>      [03s3/s3.1/parse] end elapsed_ms=_",
+                "4 rooms",
+                "[WAR]=>      [03s3/s3.1/solve] slow",
+                "[INF]=>      [03s3/s3.1/solve] end elapsed_ms=_",
+                "[INF]=>    [03s3/s3.1] end elapsed_ms=_",
+                "[INF]=>  [03s3] end elapsed_ms=_",
+                "[INF]=>done",
+            ]
+        );
+        let json = json.borrow();
+        assert!(json[3].contains(r#""question":"03s3","testcase":"s3.1","span":"03s3/s3.1/solve""#));
+        assert!(json[6].contains(r#""question":"03s3","testcase":null,"span":"03s3""#));
+        assert!(json[7].contains(r#""question":null,"testcase":null,"span":null"#));
+    }
+
+    #[test]
+    fn log_filter_by_span() {
+        let buffer = LineBuffer::default();
+        let filter = LogFilter::parse("solve=debug,io=error", DebugLevel::Info).unwrap();
+        let mut logger = Logger::new(MemIoWriteProvider::new_writer(&buffer), DebugLevel::Warn)
+            .with_filter(filter);
+        logger.debug("hidden debug");
+        logger.info("shown information");
+        {
+            let mut solve = logger.span("solve");
+            solve.trace("hidden trace");
+            solve.debug("room [[3]]");
+            let mut io = solve.span("io");
+            io.warn("hidden warning");
+            io.answer("4 rooms");
+        }
+        let lines = buffer.borrow();
+        let lines: Vec<&str> = lines
+            .iter()
+            .map(|line| line.split(" elapsed_ms=").next().unwrap())
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                "[INF]=>shown information",
+                "[DBG]=>  [solve] room 3",
+                "4 rooms",
+                "[INF]=>  [solve] end"
+            ]
+        );
+    }
 }
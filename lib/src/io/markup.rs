@@ -0,0 +1,182 @@
+// Inline highlight markup of log messages
+// Defines:
+//   [[value]]        : highlighted value.
+//   [[!error text]]  : error text.
+//   [[?hint]]        : hint, less important.
+//   \[ \] \\         : literal '[', ']' and '\', eg. "a\[\[0]]" is "a[[0]]".
+//   markup may nest, inner text keeps its own style and outer style resumes after it.
+//   unclosed "[[" and stray "]]" are kept as plain text.
+// Sample:
+//   render("room [[3]] has [[!no wall]]", false) == "room 3 has no wall"
+// Colors are ANSI escapes written directly, whether to color is decided by caller(eg. sink),
+// not by the colored crate's detection of stdout.
+//
+
+/// Style of a text run in message
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Markup {
+    Plain,
+    Value,
+    Error,
+    Hint,
+}
+
+impl Markup {
+    fn of(marker: Option<char>) -> Markup {
+        match marker {
+            Some('!') => Markup::Error,
+            Some('?') => Markup::Hint,
+            _ => Markup::Value,
+        }
+    }
+
+    fn paint(&self, text: &str) -> String {
+        match self {
+            Markup::Plain => text.to_string(),
+            Markup::Value => paint(text, "1;36"), // bold cyan
+            Markup::Error => paint(text, "1;31"), // bold red
+            Markup::Hint => paint(text, "2;3"),   // dimmed italic
+        }
+    }
+}
+
+/// An open "[[" while parsing, runs styled None inherit its style once closed.
+struct Frame {
+    style: Markup,
+    opener: String,
+    runs: Vec<(Option<Markup>, String)>,
+}
+
+impl Frame {
+    fn new(style: Markup, opener: &str) -> Frame {
+        Frame {
+            style,
+            opener: opener.to_string(),
+            runs: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, style: Option<Markup>, text: &str) {
+        match self.runs.last_mut() {
+            Some((last, run)) if *last == style => run.push_str(text),
+            _ => self.runs.push((style, text.to_string())),
+        }
+    }
+}
+
+/// Split message into styled text runs, markup removed.
+pub fn parse(msg: &str) -> Vec<(Markup, String)> {
+    let mut stack = vec![Frame::new(Markup::Plain, "")];
+    let mut chars = msg.chars().peekable();
+    while let Some(c) = chars.next() {
+        let depth = stack.len();
+        let top = stack.last_mut().unwrap();
+        match (c, chars.peek().copied()) {
+            ('\\', Some(next @ ('[' | ']' | '\\'))) => {
+                chars.next();
+                top.push(None, &next.to_string());
+            }
+            ('[', Some('[')) => {
+                chars.next();
+                let marker = chars.next_if(|m| *m == '!' || *m == '?');
+                let opener = format!("[[{}", marker.map(String::from).unwrap_or_default());
+                stack.push(Frame::new(Markup::of(marker), &opener));
+            }
+            (']', Some(']')) if depth > 1 => {
+                chars.next();
+                let closed = stack.pop().unwrap();
+                let parent = stack.last_mut().unwrap();
+                for (style, text) in closed.runs {
+                    parent.push(Some(style.unwrap_or(closed.style)), &text);
+                }
+            }
+            _ => top.push(None, &c.to_string()),
+        }
+    }
+    // unclosed markup is plain text of its parent
+    while stack.len() > 1 {
+        let unclosed = stack.pop().unwrap();
+        let parent = stack.last_mut().unwrap();
+        parent.push(None, &unclosed.opener);
+        for (style, text) in unclosed.runs {
+            parent.push(style, &text);
+        }
+    }
+    let root = stack.pop().unwrap();
+    root.runs
+        .into_iter()
+        .map(|(style, text)| (style.unwrap_or(Markup::Plain), text))
+        .collect()
+}
+
+/// Text in ANSI SGR style, eg. paint("3", "1;36") is bold cyan "3". empty text is kept empty.
+pub fn paint(text: &str, sgr: &str) -> String {
+    match text.is_empty() {
+        true => String::new(),
+        false => format!("\x1b[{}m{}\x1b[0m", sgr, text),
+    }
+}
+
+/// Render message with colored styles, or as plain text with markup stripped.
+pub fn render(msg: &str, colored: bool) -> String {
+    parse(msg)
+        .iter()
+        .map(|(style, text)| match colored {
+            true => style.paint(text),
+            false => text.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_markup_runs() {
+        assert_eq!(
+            parse("wood [[105]], [[!no room]] [[?check plan]]"),
+            [
+                (Markup::Plain, "wood ".to_string()),
+                (Markup::Value, "105".to_string()),
+                (Markup::Plain, ", ".to_string()),
+                (Markup::Error, "no room".to_string()),
+                (Markup::Plain, " ".to_string()),
+                (Markup::Hint, "check plan".to_string()),
+            ]
+        );
+        assert_eq!(
+            parse("[[!bad [[3]] rooms]]"),
+            [
+                (Markup::Error, "bad ".to_string()),
+                (Markup::Value, "3".to_string()),
+                (Markup::Error, " rooms".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn render_plain() {
+        assert_eq!(
+            render("room [[3]] has [[!no wall]]", false),
+            "room 3 has no wall"
+        );
+        assert_eq!(render(r"grid\[\[0]] is [[\]]]", false), "grid[[0]] is ]");
+        assert_eq!(render(r"back\\slash \n", false), r"back\slash \n");
+        assert_eq!(render("unclosed [[!a [[b]] c", false), "unclosed [[!a b c");
+        assert_eq!(render("stray ]] and [[]]", false), "stray ]] and ");
+    }
+
+    #[test]
+    fn render_colored() {
+        assert_eq!(
+            render("room [[3]] has [[!no wall]], [[?retry]]", true),
+            "room \x1b[1;36m3\x1b[0m has \x1b[1;31mno wall\x1b[0m, \x1b[2;3mretry\x1b[0m"
+        );
+        assert_eq!(
+            render("[[!bad [[3]] rooms]]", true),
+            "\x1b[1;31mbad \x1b[0m\x1b[1;36m3\x1b[0m\x1b[1;31m rooms\x1b[0m"
+        );
+        assert_eq!(render("no markup", true), "no markup");
+    }
+}
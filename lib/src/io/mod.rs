@@ -1,13 +1,89 @@
 pub mod debug;
 pub mod input;
+pub mod locator;
 pub mod log;
+pub mod markup;
+pub mod output;
 pub mod provider;
+pub mod record;
+pub mod scanner;
+pub mod sink;
 // pub mod tbl;
 
+use crate::io::provider::fileio::parse_i64s;
+use std::io;
+
+/// Line writer, a line ending "\n" is appended to each line.
 pub trait IoWriter {
-    fn write_line(&mut self, content: &str) -> Option<bool>;
+    fn write_line(&mut self, content: &str) -> io::Result<()>;
+
+    /// Write out lines buffered by writer, if any.
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
 }
 
+/// Line reader, line ending("\n" or "\r\n") is removed and other spaces are kept.
+///   Ok(None) at end of input, Err only if reading failed.
 pub trait IoReader {
-    fn read_line(&mut self) -> Option<String>;
+    /// Next line's raw bytes.
+    fn read_bytes(&mut self) -> io::Result<Option<Vec<u8>>>;
+
+    /// Next line's raw bytes, without consuming it.
+    fn peek_bytes(&mut self) -> io::Result<Option<&[u8]>>;
+
+    /// Consume next line, false at end of input.
+    ///   eg. after peek_bytes, providers may override it to skip without a copy.
+    fn skip_line(&mut self) -> io::Result<bool> {
+        Ok(self.read_bytes()?.is_some())
+    }
+
+    /// Next line, non UTF-8 bytes are replaced with U+FFFD.
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        Ok(self
+            .read_bytes()?
+            .map(|bytes| match String::from_utf8(bytes) {
+                Ok(line) => line,
+                Err(e) => String::from_utf8_lossy(e.as_bytes()).into_owned(),
+            }))
+    }
+
+    /// Next line, without consuming it.
+    fn peek_line(&mut self) -> io::Result<Option<String>> {
+        Ok(self
+            .peek_bytes()?
+            .map(|bytes| String::from_utf8_lossy(bytes).into_owned()))
+    }
+
+    /// Whether no line left.
+    fn is_eof(&mut self) -> io::Result<bool> {
+        Ok(self.peek_bytes()?.is_none())
+    }
+
+    /// All lines left.
+    fn read_to_end(&mut self) -> io::Result<Vec<String>> {
+        let mut lines = Vec::new();
+        while let Some(line) = self.read_line()? {
+            lines.push(line);
+        }
+        Ok(lines)
+    }
+
+    /// Read whitespace separated integers in a single line.
+    ///   InvalidData error if any token is not an integer, the line is consumed anyway.
+    ///   providers may override it with a buffer reusing fast path.
+    fn read_i64s(&mut self) -> io::Result<Option<Vec<i64>>> {
+        match self.read_bytes()? {
+            Some(line) => parse_i64s(&line).map(Some).ok_or_else(|| not_i64s(&line)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// InvalidData error of read_i64s
+pub(crate) fn not_i64s(line: &[u8]) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("expect integers, found '{}'", String::from_utf8_lossy(line)),
+    )
 }
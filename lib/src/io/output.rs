@@ -0,0 +1,146 @@
+use crate::io::provider::adapter::BufIoWriter;
+use crate::io::IoWriter;
+use std::fmt::Display;
+use std::io;
+
+/// CCC Testcase Output
+/// companion of TcFileInput, formats answers over any IoWriter.
+///   lines are kept in buffer until flush() or drop, so answers are written in one batch.
+///   call flush() to observe write errors, drop only reports them to stderr.
+pub struct TcFileOutput {
+    output_handle: Box<dyn IoWriter>,
+    lines: Vec<String>, // not flushed yet
+}
+
+impl TcFileOutput {
+    pub fn new(output_handle: Box<dyn IoWriter>) -> TcFileOutput {
+        TcFileOutput {
+            output_handle,
+            lines: Vec::new(),
+        }
+    }
+
+    /// Output to judge stdout, buffered by BufIoWriter too.
+    pub fn stdout() -> TcFileOutput {
+        TcFileOutput::new(BufIoWriter::new_writer(std::io::stdout()))
+    }
+
+    /// Write a line as it is.
+    pub fn write_line(&mut self, line: &str) {
+        self.lines.push(line.to_string());
+    }
+
+    /// Write int in a single line.
+    pub fn write_int(&mut self, n: i64) {
+        self.lines.push(n.to_string());
+    }
+
+    /// Write float in a single line, with precision digits after point.
+    pub fn write_float(&mut self, x: f64, precision: usize) {
+        self.lines.push(format!("{:.*}", precision, x));
+    }
+
+    /// Write vec in a single line, items seperated by sep.
+    pub fn write_vec<T: Display>(&mut self, items: &[T], sep: &str) {
+        let items: Vec<String> = items.iter().map(|item| item.to_string()).collect();
+        self.lines.push(items.join(sep));
+    }
+
+    /// Write character grid, a row in each line.
+    pub fn write_grid(&mut self, grid: &[Vec<char>]) {
+        self.lines
+            .extend(grid.iter().map(|row| row.iter().collect::<String>()));
+    }
+
+    /// Write "Yes" in a single line.
+    pub fn yes(&mut self) {
+        self.write_line("Yes");
+    }
+
+    /// Write "No" in a single line.
+    pub fn no(&mut self) {
+        self.write_line("No");
+    }
+
+    /// Write "Yes" if cond is true, otherwise "No".
+    pub fn write_yes_no(&mut self, cond: bool) {
+        if cond {
+            self.yes()
+        } else {
+            self.no()
+        }
+    }
+
+    /// Write buffered lines to writer and flush it.
+    ///   lines after a failed one are dropped.
+    pub fn flush(&mut self) -> io::Result<()> {
+        for line in self.lines.drain(..) {
+            self.output_handle.write_line(&line)?;
+        }
+        self.output_handle.flush()
+    }
+}
+
+impl Drop for TcFileOutput {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            eprintln!("output flush failed: {}", e);
+        }
+    }
+}
+
+/// Write values of tuple in a single line, seperated by space.
+/// eg. write_tuple!(output, rooms, left);
+#[macro_export]
+macro_rules! write_tuple {
+    ($obj:expr, $($value:expr),+ $(,)?) => {
+        $obj.write_line(&[$($value.to_string()),+].join(" "))
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io::provider::memio::{LineBuffer, MemIoWriteProvider};
+
+    #[test]
+    fn write_formatted_lines() {
+        let buffer = LineBuffer::default();
+        let mut output = TcFileOutput::new(MemIoWriteProvider::new_writer(&buffer));
+        output.write_int(-105);
+        output.write_float(2.0 / 3.0, 3);
+        output.write_vec(&[1, 2, 3], " ");
+        output.write_vec(&["a", "b"], ",");
+        output.write_grid(&[vec!['I', '.'], vec!['.', 'I']]);
+        crate::write_tuple!(output, 4, "rooms", 1.5);
+        output.write_yes_no(true);
+        output.no();
+        assert!(buffer.borrow().is_empty());
+        output.flush().unwrap();
+        assert_eq!(
+            *buffer.borrow(),
+            [
+                "-105",
+                "0.667",
+                "1 2 3",
+                "a,b",
+                "I.",
+                ".I",
+                "4 rooms 1.5",
+                "Yes",
+                "No"
+            ]
+        );
+    }
+
+    #[test]
+    fn flush_on_drop() {
+        let buffer = LineBuffer::default();
+        let mut output = TcFileOutput::new(MemIoWriteProvider::new_writer(&buffer));
+        output.yes();
+        output.flush().unwrap();
+        output.write_int(7);
+        drop(output);
+        assert_eq!(*buffer.borrow(), ["Yes", "7"]);
+    }
+}
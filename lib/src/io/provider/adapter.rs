@@ -0,0 +1,186 @@
+/// Composable IoWriter adapters
+/// Defines:
+///   TeeIoWriter    : fans out each line to several writers.
+///   RecordIoWriter : records each line with elapsed time since created.
+///   BufIoWriter    : buffers lines of a std Write, flushed on drop.
+/// Sample(show answer and save it for diffing):
+///   let record = Recording::default();
+///   let mut out = TeeIoWriter::new_writer(vec![
+///       StdIoProvider::new_writer(true).unwrap(),
+///       RecordIoWriter::new_writer(&record),
+///   ]);
+///
+use crate::io::IoWriter;
+use debug_ignore::DebugIgnore;
+use std::cell::RefCell;
+use std::io::{self, BufWriter, Write};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Lines recorded by RecordIoWriter with elapsed time, shared with the caller.
+pub type Recording = Rc<RefCell<Vec<(Duration, String)>>>;
+
+/// Writer fanning out each line to all writers in order.
+#[derive(Debug)]
+pub struct TeeIoWriter {
+    writers: DebugIgnore<Vec<Box<dyn IoWriter>>>,
+}
+
+impl IoWriter for TeeIoWriter {
+    /// write to every writer even if some failed, the first error is returned.
+    fn write_line(&mut self, content: &str) -> io::Result<()> {
+        let results: Vec<io::Result<()>> = self
+            .writers
+            .iter_mut()
+            .map(|writer| writer.write_line(content))
+            .collect();
+        results.into_iter().collect()
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let results: Vec<io::Result<()>> = self
+            .writers
+            .iter_mut()
+            .map(|writer| writer.flush())
+            .collect();
+        results.into_iter().collect()
+    }
+}
+
+impl TeeIoWriter {
+    pub fn new_writer(writers: Vec<Box<dyn IoWriter>>) -> Box<dyn IoWriter> {
+        Box::new(TeeIoWriter {
+            writers: writers.into(),
+        })
+    }
+}
+
+/// Writer recording each line with elapsed time since created.
+#[derive(Debug)]
+pub struct RecordIoWriter {
+    record: Recording,
+    started: Instant,
+}
+
+impl IoWriter for RecordIoWriter {
+    fn write_line(&mut self, content: &str) -> io::Result<()> {
+        let elapsed = self.started.elapsed();
+        self.record
+            .borrow_mut()
+            .push((elapsed, content.to_string()));
+        Ok(())
+    }
+}
+
+impl RecordIoWriter {
+    pub fn new_writer(record: &Recording) -> Box<dyn IoWriter> {
+        Box::new(RecordIoWriter {
+            record: Rc::clone(record),
+            started: Instant::now(),
+        })
+    }
+}
+
+/// Buffered line writer over std Write, flushed on drop instead of per line.
+///   flush errors on drop go to stderr, call flush() to handle them.
+#[derive(Debug)]
+pub struct BufIoWriter<W: Write> {
+    output_handle: BufWriter<W>,
+}
+
+impl<W: Write> IoWriter for BufIoWriter<W> {
+    fn write_line(&mut self, content: &str) -> io::Result<()> {
+        writeln!(self.output_handle, "{}", content)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.output_handle.flush()
+    }
+}
+
+impl<W: Write> Drop for BufIoWriter<W> {
+    fn drop(&mut self) {
+        if let Err(e) = self.output_handle.flush() {
+            eprintln!("buffered flush failed: {}", e);
+        }
+    }
+}
+
+impl<W: Write + 'static> BufIoWriter<W> {
+    pub fn new_writer(output: W) -> Box<dyn IoWriter> {
+        Box::new(BufIoWriter {
+            output_handle: BufWriter::new(output),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io::provider::memio::{LineBuffer, MemIoWriteProvider};
+
+    struct FailedWriter;
+
+    impl IoWriter for FailedWriter {
+        fn write_line(&mut self, _content: &str) -> io::Result<()> {
+            Err(io::Error::other("failed"))
+        }
+    }
+
+    #[test]
+    fn tee_to_all_writers() {
+        let (first, second) = (LineBuffer::default(), LineBuffer::default());
+        let mut tee = TeeIoWriter::new_writer(vec![
+            MemIoWriteProvider::new_writer(&first),
+            MemIoWriteProvider::new_writer(&second),
+        ]);
+        tee.write_line("4 rooms").unwrap();
+        assert_eq!(*first.borrow(), ["4 rooms"]);
+        assert_eq!(*second.borrow(), ["4 rooms"]);
+
+        let mut tee = TeeIoWriter::new_writer(vec![
+            Box::new(FailedWriter),
+            MemIoWriteProvider::new_writer(&second),
+        ]);
+        assert_eq!(
+            tee.write_line("left over").unwrap_err().to_string(),
+            "failed"
+        );
+        assert_eq!(*second.borrow(), ["4 rooms", "left over"]);
+    }
+
+    #[test]
+    fn record_lines_in_order() {
+        let record = Recording::default();
+        let mut writer = RecordIoWriter::new_writer(&record);
+        writer.write_line("first").unwrap();
+        writer.write_line("second").unwrap();
+        let record = record.borrow();
+        let lines: Vec<&str> = record.iter().map(|(_, l)| l.as_str()).collect();
+        assert_eq!(lines, ["first", "second"]);
+        assert!(record[0].0 <= record[1].0);
+    }
+
+    #[test]
+    fn buffer_until_drop() {
+        let shared = Rc::new(RefCell::new(Vec::<u8>::new()));
+        struct SharedBytes(Rc<RefCell<Vec<u8>>>);
+        impl Write for SharedBytes {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.borrow_mut().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+        let mut writer = BufIoWriter::new_writer(SharedBytes(Rc::clone(&shared)));
+        writer.write_line("1 2 3").unwrap();
+        assert!(shared.borrow().is_empty());
+        writer.flush().unwrap();
+        assert_eq!(*shared.borrow(), b"1 2 3\n");
+        writer.write_line("yes").unwrap();
+        drop(writer);
+        assert_eq!(*shared.borrow(), b"1 2 3\nyes\n");
+    }
+}
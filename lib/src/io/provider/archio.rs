@@ -0,0 +1,212 @@
+/// Archive testcase provider
+/// Defines:
+///   archive : .zip or .tar.gz(.tgz) bundle of testcase files, eg. *.in/*.out pairs.
+///   member  : file path inside archive, directories are not members.
+/// Sample:
+///   let members = ArchIoReadProvider::members(Path::new("ccc03s3.zip"))?;
+///   let reader = ArchIoReadProvider::open_member(Path::new("ccc03s3.zip"), &members[0])?;
+///
+use crate::io::provider::fileio::FileIoReadProvider;
+use crate::io::IoReader;
+use flate2::read::GzDecoder;
+use std::fs::File;
+use std::io::{self, Cursor, Read};
+use std::path::Path;
+
+/// Supported archive formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Zip,
+    TarGz,
+}
+
+impl ArchiveKind {
+    /// Archive kind by file name extension, None if not an archive
+    pub fn of(path: &Path) -> Option<ArchiveKind> {
+        let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+        if name.ends_with(".zip") {
+            Some(ArchiveKind::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveKind::TarGz)
+        } else {
+            None
+        }
+    }
+
+    /// File name without archive extension. eg. ccc03s3.tar.gz => ccc03s3
+    pub fn stem(path: &Path) -> Option<&str> {
+        let name = path.file_name()?.to_str()?;
+        let ext_len = match ArchiveKind::of(path)? {
+            ArchiveKind::Zip => ".zip".len(),
+            ArchiveKind::TarGz if name.to_ascii_lowercase().ends_with(".tgz") => ".tgz".len(),
+            ArchiveKind::TarGz => ".tar.gz".len(),
+        };
+        Some(&name[..name.len() - ext_len])
+    }
+}
+
+/// Reader of files inside archives.
+///   a member is decompressed into memory once opened, then read as FileIoReadProvider does.
+pub struct ArchIoReadProvider;
+
+impl ArchIoReadProvider {
+    /// File members in archive order
+    pub fn members(archive: &Path) -> io::Result<Vec<String>> {
+        let file = File::open(archive)?;
+        match ArchiveKind::of(archive) {
+            Some(ArchiveKind::Zip) => {
+                let mut zip = zip::ZipArchive::new(file)?;
+                let mut members = Vec::new();
+                for idx in 0..zip.len() {
+                    let entry = zip.by_index(idx)?;
+                    if entry.is_file() {
+                        members.push(entry.name().to_string());
+                    }
+                }
+                Ok(members)
+            }
+            Some(ArchiveKind::TarGz) => {
+                let mut tar = tar::Archive::new(GzDecoder::new(file));
+                let mut members = Vec::new();
+                for entry in tar.entries()? {
+                    let entry = entry?;
+                    if entry.header().entry_type().is_file() {
+                        members.push(entry.path()?.to_string_lossy().to_string());
+                    }
+                }
+                Ok(members)
+            }
+            None => Err(not_archive(archive)),
+        }
+    }
+
+    /// Decompressed bytes of member
+    pub fn read_member(archive: &Path, member: &str) -> io::Result<Vec<u8>> {
+        let file = File::open(archive)?;
+        let mut bytes = Vec::new();
+        match ArchiveKind::of(archive) {
+            Some(ArchiveKind::Zip) => {
+                let mut zip = zip::ZipArchive::new(file)?;
+                zip.by_name(member)?.read_to_end(&mut bytes)?;
+                return Ok(bytes);
+            }
+            Some(ArchiveKind::TarGz) => {
+                let mut tar = tar::Archive::new(GzDecoder::new(file));
+                for entry in tar.entries()? {
+                    let mut entry = entry?;
+                    if entry.path()?.to_string_lossy() == member {
+                        entry.read_to_end(&mut bytes)?;
+                        return Ok(bytes);
+                    }
+                }
+            }
+            None => return Err(not_archive(archive)),
+        }
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("member '{}' not found in '{}'", member, archive.display()),
+        ))
+    }
+
+    /// Line reader of member, named as archive!member
+    pub fn open_member(archive: &Path, member: &str) -> io::Result<Box<dyn IoReader>> {
+        let bytes = ArchIoReadProvider::read_member(archive, member)?;
+        let name = format!("{}!{}", archive.display(), member);
+        let reader = Box::new(Cursor::new(bytes));
+        Ok(Box::new(FileIoReadProvider::new(&name, reader)))
+    }
+}
+
+fn not_archive(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("'{}' is not a zip or tar.gz archive", path.display()),
+    )
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use std::io::Write;
+    use std::path::PathBuf;
+    use std::{env, fs, process};
+
+    /// zip or tar.gz archive by extension of path, with (member, content) files
+    pub(crate) fn make_archive(path: &Path, files: &[(&str, &str)]) {
+        let file = File::create(path).unwrap();
+        match ArchiveKind::of(path).unwrap() {
+            ArchiveKind::Zip => {
+                let mut zip = zip::ZipWriter::new(file);
+                let options = zip::write::SimpleFileOptions::default();
+                zip.add_directory("data/", options).unwrap();
+                for (member, content) in files {
+                    zip.start_file(*member, options).unwrap();
+                    zip.write_all(content.as_bytes()).unwrap();
+                }
+                zip.finish().unwrap();
+            }
+            ArchiveKind::TarGz => {
+                let gz = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+                let mut tar = tar::Builder::new(gz);
+                for (member, content) in files {
+                    let mut header = tar::Header::new_gnu();
+                    header.set_size(content.len() as u64);
+                    header.set_mode(0o644);
+                    header.set_cksum();
+                    tar.append_data(&mut header, member, content.as_bytes())
+                        .unwrap();
+                }
+                tar.into_inner().unwrap().finish().unwrap();
+            }
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("ccc_archio_{}_{}", name, process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn archive_kind_and_stem() {
+        assert_eq!(
+            ArchiveKind::of(Path::new("a/ccc03s3.ZIP")),
+            Some(ArchiveKind::Zip)
+        );
+        assert_eq!(
+            ArchiveKind::of(Path::new("ccc03s3.tgz")),
+            Some(ArchiveKind::TarGz)
+        );
+        assert_eq!(ArchiveKind::of(Path::new("ccc03s3.tc")), None);
+        assert_eq!(
+            ArchiveKind::stem(Path::new("ccc03s3.tar.gz")),
+            Some("ccc03s3")
+        );
+        assert_eq!(ArchiveKind::stem(Path::new("ccc03s3.tgz")), Some("ccc03s3"));
+        assert_eq!(ArchiveKind::stem(Path::new("ccc03s3.tc")), None);
+    }
+
+    #[test]
+    fn read_members_of_zip_and_tar_gz() {
+        let dir = temp_dir("read");
+        let files = [("data/1.in", "3\r\n1 2 3\n"), ("data/1.out", "6\n")];
+        for name in ["ccc03s3.zip", "ccc03s3.tar.gz"] {
+            let archive = dir.join(name);
+            make_archive(&archive, &files);
+            assert_eq!(
+                ArchIoReadProvider::members(&archive).unwrap(),
+                ["data/1.in", "data/1.out"]
+            );
+            let mut reader = ArchIoReadProvider::open_member(&archive, "data/1.in").unwrap();
+            assert_eq!(reader.read_line().unwrap().as_deref(), Some("3"));
+            assert_eq!(reader.read_i64s().unwrap(), Some(vec![1, 2, 3]));
+            assert_eq!(reader.read_line().unwrap(), None);
+            let err = ArchIoReadProvider::read_member(&archive, "data/2.in").unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        }
+        fs::write(dir.join("ccc03s3.tc"), "1").unwrap();
+        let err = ArchIoReadProvider::members(&dir.join("ccc03s3.tc")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,175 @@
+/// Provider conformance suite
+///   the same assertions run against every IoReader and IoWriter provider.
+///   stdio is checked in a child process of this test binary, with piped stdin and stdout.
+///
+use crate::io::provider::adapter::{RecordIoWriter, Recording, TeeIoWriter};
+use crate::io::provider::archio::test::make_archive;
+use crate::io::provider::archio::ArchIoReadProvider;
+use crate::io::provider::fastio::FastIoReadProvider;
+use crate::io::provider::fileio::{FileIoReadProvider, FileIoWriteProvider};
+use crate::io::provider::memio::{LineBuffer, MemIoReadProvider, MemIoWriteProvider};
+use crate::io::provider::stdio::StdIoProvider;
+use crate::io::scanner::Scanner;
+use crate::io::{IoReader, IoWriter};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::{env, fs, process};
+
+/// reader input: CRLF and LF endings, spaces kept, no line ending at last line
+const INPUT: &str = "1 -2 +3\r\n  ab\u{e9}c  \r\n\n4 x\nlast";
+
+/// writer lines and their expected output
+const LINES: [&str; 3] = ["first line", "", "  spaced  "];
+const OUTPUT: &str = "first line\n\n  spaced  \n";
+
+/// env var set in the child process of stdio conformance
+const CHILD_ENV: &str = "CCC_CONFORMANCE_CHILD";
+
+fn check_reader(name: &str, mut reader: Box<dyn IoReader>) {
+    assert!(!reader.is_eof().unwrap(), "{name}");
+    assert_eq!(
+        reader.peek_line().unwrap().as_deref(),
+        Some("1 -2 +3"),
+        "{name}"
+    );
+    assert_eq!(
+        reader.peek_line().unwrap().as_deref(),
+        Some("1 -2 +3"),
+        "{name}"
+    );
+    assert_eq!(reader.read_i64s().unwrap(), Some(vec![1, -2, 3]), "{name}");
+    let spaced = "  ab\u{e9}c  ".as_bytes();
+    assert_eq!(reader.peek_bytes().unwrap(), Some(spaced), "{name}");
+    assert_eq!(
+        reader.read_bytes().unwrap().as_deref(),
+        Some(spaced),
+        "{name}"
+    );
+    assert_eq!(reader.read_line().unwrap().as_deref(), Some(""), "{name}");
+    let err = reader.read_i64s().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{name}");
+    assert_eq!(reader.read_to_end().unwrap(), ["last"], "{name}");
+    assert!(reader.is_eof().unwrap(), "{name}");
+    assert_eq!(reader.peek_bytes().unwrap(), None, "{name}");
+    assert_eq!(reader.read_line().unwrap(), None, "{name}");
+    assert_eq!(reader.read_i64s().unwrap(), None, "{name}");
+    assert!(reader.read_to_end().unwrap().is_empty(), "{name}");
+}
+
+fn write_lines(mut writer: Box<dyn IoWriter>) {
+    for line in LINES {
+        writer.write_line(line).unwrap();
+    }
+    writer.flush().unwrap();
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("ccc_conformance_{}_{}", name, process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn readers_conform() {
+    let dir = temp_dir("readers");
+    let path = dir.join("input.tc");
+    fs::write(&path, INPUT).unwrap();
+    let file_path = path.to_str().unwrap();
+    check_reader(
+        "file",
+        FileIoReadProvider::open_for_read(file_path).unwrap(),
+    );
+    #[cfg(feature = "mmap")]
+    check_reader("mmap", FileIoReadProvider::open_mapped(file_path).unwrap());
+
+    let fast = FastIoReadProvider::from_reader(INPUT.as_bytes()).unwrap();
+    check_reader("fast", fast);
+    check_reader("mem text", MemIoReadProvider::from_text(INPUT));
+    let lines = INPUT.lines().map(String::from).collect();
+    check_reader("mem lines", MemIoReadProvider::from_lines(lines));
+
+    for name in ["input.zip", "input.tar.gz"] {
+        let archive = dir.join(name);
+        make_archive(&archive, &[("data/1.in", INPUT)]);
+        let reader = ArchIoReadProvider::open_member(&archive, "data/1.in").unwrap();
+        check_reader(name, reader);
+    }
+
+    let scanner = Scanner::new(MemIoReadProvider::from_text(INPUT));
+    check_reader("scanner", Box::new(scanner));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn writers_conform() {
+    let dir = temp_dir("writers");
+    let path = dir.join("output.txt");
+    let file_path = path.to_str().unwrap();
+    write_lines(FileIoWriteProvider::open_for_write(file_path).unwrap());
+    assert_eq!(fs::read_to_string(&path).unwrap(), OUTPUT, "file");
+    write_lines(FileIoWriteProvider::open_buffered(file_path).unwrap());
+    assert_eq!(fs::read_to_string(&path).unwrap(), OUTPUT, "buffered file");
+    fs::remove_dir_all(&dir).unwrap();
+
+    let (first, second) = (LineBuffer::default(), LineBuffer::default());
+    write_lines(MemIoWriteProvider::new_writer(&first));
+    assert_eq!(first.borrow().join("\n") + "\n", OUTPUT, "mem");
+
+    let record = Recording::default();
+    write_lines(TeeIoWriter::new_writer(vec![
+        MemIoWriteProvider::new_writer(&second),
+        RecordIoWriter::new_writer(&record),
+    ]));
+    assert_eq!(second.borrow().join("\n") + "\n", OUTPUT, "tee");
+    let record = record.borrow();
+    let recorded: Vec<&str> = record.iter().map(|(_, l)| l.as_str()).collect();
+    assert_eq!(recorded.join("\n") + "\n", OUTPUT, "record");
+}
+
+#[test]
+fn stdio_conform() {
+    for reader in ["std", "fast"] {
+        check_stdio_child(reader);
+    }
+}
+
+fn check_stdio_child(reader: &str) {
+    let mut child = Command::new(env::current_exe().unwrap())
+        .args(["io::provider::conformance::stdio_child", "--exact"])
+        .args(["--nocapture", "--test-threads=1"])
+        .env(CHILD_ENV, reader)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(INPUT.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}: {}{}",
+        reader,
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("1 passed"), "{stdout}");
+    assert!(stdout.contains(OUTPUT), "{stdout}");
+}
+
+/// run by stdio_conform only, passes trivially otherwise
+#[test]
+fn stdio_child() {
+    match env::var(CHILD_ENV).as_deref() {
+        Ok("std") => check_reader("stdin", StdIoProvider::new_reader(true).unwrap()),
+        Ok("fast") => check_reader("fast stdin", FastIoReadProvider::stdin().unwrap()),
+        _ => return,
+    }
+    write_lines(StdIoProvider::new_writer(true).unwrap());
+}
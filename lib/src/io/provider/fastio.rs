@@ -0,0 +1,135 @@
+/// Fast input reader for large judge inputs
+///   whole input is read once into a byte buffer, lines are slices of it.
+///   no lock per line, no UTF-8 validation or String allocation in peek_bytes and read_i64s.
+/// Sample(10^6 integers from stdin):
+///   let mut reader = FastIoReadProvider::stdin().unwrap();
+///   while let Some(nums) = reader.read_i64s().unwrap() { ... }
+///
+use crate::io::provider::fileio::parse_i64;
+use crate::io::{not_i64s, IoReader};
+use std::io::{self, Read};
+
+#[derive(Debug)]
+pub struct FastIoReadProvider {
+    buf: Vec<u8>,
+    pos: usize, // start of next line
+}
+
+impl IoReader for FastIoReadProvider {
+    fn read_bytes(&mut self) -> io::Result<Option<Vec<u8>>> {
+        Ok(self.next_line().map(<[u8]>::to_vec))
+    }
+
+    fn peek_bytes(&mut self) -> io::Result<Option<&[u8]>> {
+        let start = self.pos;
+        Ok(self.line_end(start).map(|(end, _)| &self.buf[start..end]))
+    }
+
+    fn skip_line(&mut self) -> io::Result<bool> {
+        Ok(self.next_line().is_some())
+    }
+
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        let line = self.next_line();
+        Ok(line.map(|bytes| String::from_utf8_lossy(bytes).into_owned()))
+    }
+
+    fn is_eof(&mut self) -> io::Result<bool> {
+        Ok(self.pos >= self.buf.len())
+    }
+
+    /// parse integers in one pass over buffer, no line scanning ahead.
+    fn read_i64s(&mut self) -> io::Result<Option<Vec<i64>>> {
+        if self.pos >= self.buf.len() {
+            return Ok(None);
+        }
+        match self.parse_line_i64s() {
+            Some(nums) => Ok(Some(nums)),
+            None => {
+                let line = self.next_line().unwrap_or_default();
+                Err(not_i64s(line))
+            }
+        }
+    }
+}
+
+impl FastIoReadProvider {
+    /// Read all of judge stdin at once.
+    pub fn stdin() -> io::Result<Box<dyn IoReader>> {
+        FastIoReadProvider::from_reader(io::stdin().lock())
+    }
+
+    /// Read all of a std Read at once.
+    pub fn from_reader(mut input: impl Read) -> io::Result<Box<dyn IoReader>> {
+        let mut buf = Vec::new();
+        input.read_to_end(&mut buf)?;
+        Ok(Box::new(FastIoReadProvider { buf, pos: 0 }))
+    }
+
+    /// (end, next) of line starting at pos: end excludes line ending, next is start of next line
+    fn line_end(&self, pos: usize) -> Option<(usize, usize)> {
+        if pos >= self.buf.len() {
+            return None;
+        }
+        Some(match self.buf[pos..].iter().position(|&b| b == b'\n') {
+            Some(len) if len > 0 && self.buf[pos + len - 1] == b'\r' => {
+                (pos + len - 1, pos + len + 1)
+            }
+            Some(len) => (pos + len, pos + len + 1),
+            None => (self.buf.len(), self.buf.len()),
+        })
+    }
+
+    /// integers of line at pos, and pos moved to next line.
+    ///   None if any token is not an integer, pos is not moved then.
+    fn parse_line_i64s(&mut self) -> Option<Vec<i64>> {
+        let mut nums = Vec::new();
+        let mut pos = self.pos;
+        while let Some(&b) = self.buf.get(pos) {
+            match b {
+                b'\n' => {
+                    pos += 1;
+                    break;
+                }
+                b if b.is_ascii_whitespace() => pos += 1,
+                _ => {
+                    let start = pos;
+                    while self.buf.get(pos).is_some_and(|b| !b.is_ascii_whitespace()) {
+                        pos += 1;
+                    }
+                    nums.push(parse_i64(&self.buf[start..pos])?);
+                }
+            }
+        }
+        self.pos = pos;
+        Some(nums)
+    }
+
+    fn next_line(&mut self) -> Option<&[u8]> {
+        let start = self.pos;
+        let (end, next) = self.line_end(start)?;
+        self.pos = next;
+        Some(&self.buf[start..end])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_i64s_in_place() {
+        let input = b"\t12 -7\r\n+0 1-2\n\n3 x\n99999999999999999999\n4 5\n-9223372036854775808";
+        let mut reader = FastIoReadProvider::from_reader(&input[..]).unwrap();
+        assert_eq!(reader.read_i64s().unwrap(), Some(vec![12, -7]));
+        let err = reader.read_i64s().unwrap_err();
+        assert_eq!(err.to_string(), "expect integers, found '+0 1-2'");
+        assert_eq!(reader.read_i64s().unwrap(), Some(vec![]));
+        assert!(reader.read_i64s().is_err());
+        assert!(reader.read_i64s().is_err());
+        assert_eq!(reader.peek_line().unwrap().as_deref(), Some("4 5"));
+        assert_eq!(reader.read_i64s().unwrap(), Some(vec![4, 5]));
+        assert_eq!(reader.read_i64s().unwrap(), Some(vec![i64::MIN]));
+        assert_eq!(reader.read_i64s().unwrap(), None);
+    }
+}
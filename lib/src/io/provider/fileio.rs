@@ -1,46 +1,111 @@
-use crate::io::{IoReader, IoWriter};
+use crate::io::provider::adapter::BufIoWriter;
+use crate::io::provider::lines::LineBuf;
+use crate::io::{not_i64s, IoReader, IoWriter};
+use debug_ignore::DebugIgnore;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Write};
+use std::io::{self, BufRead, BufReader, Write};
 
-#[derive(Debug, Clone)]
+/// files not smaller than this are memory mapped, with feature "mmap"
+#[cfg(feature = "mmap")]
+pub const MMAP_THRESHOLD: u64 = 16 << 20;
+
+/// Streaming file reader, one line in memory at a time.
+#[derive(Debug)]
 pub struct FileIoReadProvider {
     pub file_path: String,
-    pub lines: Vec<String>,
+    reader: DebugIgnore<Box<dyn BufRead>>,
+    line: LineBuf,
 }
 
 impl IoReader for FileIoReadProvider {
-    fn read_line(&mut self) -> Option<String> {
-        self.lines.pop()
+    fn read_bytes(&mut self) -> io::Result<Option<Vec<u8>>> {
+        Ok(self.line.next(&mut **self.reader)?.map(<[u8]>::to_vec))
+    }
+
+    fn peek_bytes(&mut self) -> io::Result<Option<&[u8]>> {
+        self.line.peek(&mut **self.reader)
+    }
+
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        let line = self.line.next(&mut **self.reader)?;
+        Ok(line.map(|bytes| String::from_utf8_lossy(bytes).into_owned()))
+    }
+
+    fn read_i64s(&mut self) -> io::Result<Option<Vec<i64>>> {
+        match self.line.next(&mut **self.reader)? {
+            Some(line) => parse_i64s(line).map(Some).ok_or_else(|| not_i64s(line)),
+            None => Ok(None),
+        }
     }
 }
 
 impl FileIoReadProvider {
     /// open_for_read:
-    /// open a existing file, then read lines to FileIoReadProvider.lines[]
-    pub fn open_for_read(file_path: &str) -> Option<Box<dyn IoReader>> {
-        let file_path = String::from(file_path);
-        let mut read_provider = FileIoReadProvider {
-            file_path,
-            lines: vec![],
-        };
-        let r = File::open(read_provider.file_path.clone());
-        match r {
-            Ok(handle) => {
-                let buffer = BufReader::new(handle);
-                for line in buffer.lines() {
-                    read_provider
-                        .lines
-                        .push(line.unwrap().trim_end().to_string());
-                }
-                read_provider.lines.reverse();
-                Some(Box::new(read_provider))
-            }
-            Err(e) => {
-                println!("{e}");
-                None
-            }
+    /// open a existing file for streaming read.
+    ///   with feature "mmap", file larger than MMAP_THRESHOLD is memory mapped.
+    pub fn open_for_read(file_path: &str) -> io::Result<Box<dyn IoReader>> {
+        let handle = File::open(file_path)?;
+        #[cfg(feature = "mmap")]
+        if handle.metadata()?.len() >= MMAP_THRESHOLD {
+            return FileIoReadProvider::open_mapped(file_path);
         }
+        let reader = Box::new(BufReader::with_capacity(1 << 16, handle));
+        Ok(Box::new(FileIoReadProvider::new(file_path, reader)))
     }
+
+    /// open_mapped:
+    /// open a existing file by memory mapping, for large files.
+    #[cfg(feature = "mmap")]
+    pub fn open_mapped(file_path: &str) -> io::Result<Box<dyn IoReader>> {
+        // SAFETY: testcase files are not modified while being read.
+        let mmap = unsafe { memmap2::Mmap::map(&File::open(file_path)?)? };
+        let reader = Box::new(std::io::Cursor::new(mmap));
+        Ok(Box::new(FileIoReadProvider::new(file_path, reader)))
+    }
+
+    pub(crate) fn new(file_path: &str, reader: Box<dyn BufRead>) -> FileIoReadProvider {
+        FileIoReadProvider {
+            file_path: String::from(file_path),
+            reader: reader.into(),
+            line: LineBuf::default(),
+        }
+    }
+}
+
+/// Parse whitespace separated integers from bytes, without String allocation.
+///   None if any token is not an integer.
+pub fn parse_i64s(line: &[u8]) -> Option<Vec<i64>> {
+    line.split(u8::is_ascii_whitespace)
+        .filter(|t| !t.is_empty())
+        .map(parse_i64)
+        .collect()
+}
+
+/// Parse an integer token with optional sign, None if not an i64.
+///   negative numbers are accumulated below zero, so i64::MIN is accepted.
+pub fn parse_i64(token: &[u8]) -> Option<i64> {
+    let (neg, digits) = match token {
+        [b'-', rest @ ..] => (true, rest),
+        [b'+', rest @ ..] => (false, rest),
+        _ => (false, token),
+    };
+    if digits.is_empty() {
+        return None;
+    }
+    let mut n: i64 = 0;
+    for &b in digits {
+        if !b.is_ascii_digit() {
+            return None;
+        }
+        let d = (b - b'0') as i64;
+        n = n.checked_mul(10)?;
+        n = if neg {
+            n.checked_sub(d)?
+        } else {
+            n.checked_add(d)?
+        };
+    }
+    Some(n)
 }
 
 #[derive(Debug)]
@@ -50,55 +115,123 @@ pub struct FileIoWriteProvider {
 }
 
 impl IoWriter for FileIoWriteProvider {
-    fn write_line(&mut self, content: &str) -> Option<bool> {
-        let s = format!("{}\n", content);
-        let buf = s.as_bytes();
-        let r = self.output_handle.write_all(buf);
-        match r {
-            Ok(_) => {
-                self.output_handle.flush().unwrap();
-                return Some(true);
-            }
-            Err(_) => {
-                println!("file '{}' not exist.", self.file_path);
-                return None;
-            }
-        };
+    /// each line is flushed once written.
+    fn write_line(&mut self, content: &str) -> io::Result<()> {
+        writeln!(self.output_handle, "{}", content)
+            .and_then(|_| self.output_handle.flush())
+            .map_err(|e| {
+                let reason = format!("file '{}' write failed: {}", self.file_path, e);
+                io::Error::new(e.kind(), reason)
+            })
     }
 }
 
 impl FileIoWriteProvider {
-    pub fn open_for_write(file_path: &str) -> Option<Box<dyn IoWriter>> {
+    /// open_for_write:
+    /// create or truncate a file, each line is flushed once written.
+    pub fn open_for_write(file_path: &str) -> io::Result<Box<dyn IoWriter>> {
         let write_provider = FileIoWriteProvider {
             file_path: String::from(file_path),
-            output_handle: File::create(file_path).unwrap(),
+            output_handle: File::create(file_path)?,
         };
-        Some(Box::new(write_provider))
+        Ok(Box::new(write_provider))
+    }
+
+    /// open_buffered:
+    /// create or truncate a file, lines are flushed on drop, for large output.
+    pub fn open_buffered(file_path: &str) -> io::Result<Box<dyn IoWriter>> {
+        Ok(BufIoWriter::new_writer(File::create(file_path)?))
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use std::{env, fs, process};
+
+    #[test]
+    fn read_stream_crlf_and_invalid_utf8() {
+        let path = env::temp_dir().join(format!("ccc_fileio_stream_{}.tc", process::id()));
+        fs::write(&path, b"1 -2 +3\r\nab\xffc  \r\n\n4 x\n5").unwrap();
+        let mut reader = FileIoReadProvider::open_for_read(path.to_str().unwrap()).unwrap();
+        assert_eq!(reader.read_i64s().unwrap(), Some(vec![1, -2, 3]));
+        assert_eq!(reader.peek_bytes().unwrap(), Some(&b"ab\xffc  "[..]));
+        assert_eq!(
+            reader.read_line().unwrap().as_deref(),
+            Some("ab\u{fffd}c  ")
+        );
+        assert_eq!(reader.read_line().unwrap().as_deref(), Some(""));
+        let err = reader.read_i64s().unwrap_err();
+        assert_eq!(err.to_string(), "expect integers, found '4 x'");
+        assert_eq!(reader.read_line().unwrap().as_deref(), Some("5"));
+        assert_eq!(reader.read_line().unwrap(), None);
+        #[cfg(feature = "mmap")]
+        {
+            let mut reader = FileIoReadProvider::open_mapped(path.to_str().unwrap()).unwrap();
+            assert_eq!(reader.read_i64s().unwrap(), Some(vec![1, -2, 3]));
+            assert_eq!(
+                reader.read_line().unwrap().as_deref(),
+                Some("ab\u{fffd}c  ")
+            );
+        }
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn parse_i64s_bytes() {
+        assert_eq!(parse_i64s(b" 12\t-7 +0  "), Some(vec![12, -7, 0]));
+        assert_eq!(parse_i64s(b""), Some(vec![]));
+        assert_eq!(parse_i64s(b"1 - 2"), None);
+        assert_eq!(parse_i64s(b"1.5"), None);
+        assert_eq!(parse_i64s(b"99999999999999999999"), None);
+        assert_eq!(
+            parse_i64s(b"-9223372036854775808 9223372036854775807"),
+            Some(vec![i64::MIN, i64::MAX])
+        );
+        assert_eq!(parse_i64s(b"-9223372036854775809"), None);
+        assert_eq!(parse_i64s(b"9223372036854775808"), None);
+    }
 
     #[test]
     fn read_from_file() {
-        let reader_pack =
-            FileIoReadProvider::open_for_read("/Users/lzcoder/repos/ccc-rust/io_files/test.txt");
+        let path = env::temp_dir().join(format!("ccc_fileio_read_{}.txt", process::id()));
+        fs::write(&path, "This is a test file1.\nThis is a test file2.\n").unwrap();
+        let reader_pack = FileIoReadProvider::open_for_read(path.to_str().unwrap());
         let mut reader = reader_pack.unwrap();
-        let ln = reader.read_line().unwrap();
+        let ln = reader.read_line().unwrap().unwrap();
         assert_eq!(ln, "This is a test file1.");
-        let ln = reader.read_line().unwrap();
+        let ln = reader.read_line().unwrap().unwrap();
         assert_eq!(ln, "This is a test file2.");
+        assert_eq!(reader.read_line().unwrap(), None);
+        let absent = path.with_extension("absent");
+        let err = FileIoReadProvider::open_for_read(absent.to_str().unwrap()).err();
+        assert_eq!(err.unwrap().kind(), io::ErrorKind::NotFound);
+        fs::remove_file(&path).unwrap();
     }
 
     #[test]
     fn write_to_file() {
-        let writer =
-            FileIoWriteProvider::open_for_write("/Users/lzcoder/repos/ccc-rust/io_files/write.txt");
+        let path = env::temp_dir().join(format!("ccc_fileio_write_{}.txt", process::id()));
+        let writer = FileIoWriteProvider::open_for_write(path.to_str().unwrap());
+        let mut handle = writer.unwrap();
+        handle.write_line("This is a test file.").unwrap();
+        handle.write_line("This is another test file.").unwrap();
+        drop(handle);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "This is a test file.\nThis is another test file.\n"
+        );
+        fs::remove_file(&path).unwrap();
+
+        let writer = FileIoWriteProvider::open_buffered(path.to_str().unwrap());
         let mut handle = writer.unwrap();
-        handle.write_line("This is a test file.");
-        handle.write_line("This is another test file.");
-        assert!(true);
+        handle.write_line("buffered").unwrap();
+        drop(handle);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "buffered\n");
+        fs::remove_file(&path).unwrap();
+
+        let absent = env::temp_dir().join(format!("ccc_fileio_absent_{}", process::id()));
+        let writer = FileIoWriteProvider::open_for_write(absent.join("w.txt").to_str().unwrap());
+        assert_eq!(writer.err().unwrap().kind(), io::ErrorKind::NotFound);
     }
 }
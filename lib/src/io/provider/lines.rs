@@ -0,0 +1,51 @@
+/// Line buffer of stream providers, with one line look ahead.
+///   line ending(LF or CRLF) is removed, other bytes are kept.
+use std::io::{self, BufRead};
+
+#[derive(Debug, Default)]
+pub(crate) struct LineBuf {
+    buf: Vec<u8>,
+    state: LineState,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum LineState {
+    #[default]
+    Empty, // nothing read ahead
+    Line, // a line in buf
+    Eof,  // end of input read ahead
+}
+
+impl LineBuf {
+    /// Next line without consuming it, None at end of input
+    pub(crate) fn peek(&mut self, reader: &mut dyn BufRead) -> io::Result<Option<&[u8]>> {
+        self.fill(reader)?;
+        Ok((self.state == LineState::Line).then_some(&self.buf[..]))
+    }
+
+    /// Next line, None at end of input
+    pub(crate) fn next(&mut self, reader: &mut dyn BufRead) -> io::Result<Option<&[u8]>> {
+        self.fill(reader)?;
+        let state = std::mem::take(&mut self.state);
+        Ok((state == LineState::Line).then_some(&self.buf[..]))
+    }
+
+    fn fill(&mut self, reader: &mut dyn BufRead) -> io::Result<()> {
+        if self.state != LineState::Empty {
+            return Ok(());
+        }
+        self.buf.clear();
+        if reader.read_until(b'\n', &mut self.buf)? == 0 {
+            self.state = LineState::Eof;
+            return Ok(());
+        }
+        if self.buf.last() == Some(&b'\n') {
+            self.buf.pop();
+            if self.buf.last() == Some(&b'\r') {
+                self.buf.pop();
+            }
+        }
+        self.state = LineState::Line;
+        Ok(())
+    }
+}
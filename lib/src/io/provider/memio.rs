@@ -0,0 +1,92 @@
+use crate::io::{IoReader, IoWriter};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io;
+use std::rc::Rc;
+
+/// Lines captured by MemIoWriteProvider, shared with the test code.
+pub type LineBuffer = Rc<RefCell<Vec<String>>>;
+
+/// In-memory reader over given lines, for hermetic solver tests.
+#[derive(Debug, Clone, Default)]
+pub struct MemIoReadProvider {
+    lines: VecDeque<String>,
+}
+
+impl IoReader for MemIoReadProvider {
+    fn read_bytes(&mut self) -> io::Result<Option<Vec<u8>>> {
+        Ok(self.lines.pop_front().map(String::into_bytes))
+    }
+
+    fn peek_bytes(&mut self) -> io::Result<Option<&[u8]>> {
+        Ok(self.lines.front().map(String::as_bytes))
+    }
+
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        Ok(self.lines.pop_front())
+    }
+}
+
+impl MemIoReadProvider {
+    /// reader over text, split by LF or CRLF.
+    pub fn from_text(text: &str) -> Box<dyn IoReader> {
+        MemIoReadProvider::from_lines(text.lines().map(String::from).collect())
+    }
+
+    /// reader over lines
+    pub fn from_lines(lines: Vec<String>) -> Box<dyn IoReader> {
+        Box::new(MemIoReadProvider {
+            lines: lines.into(),
+        })
+    }
+}
+
+/// In-memory writer, captures written lines into a shared LineBuffer.
+#[derive(Debug, Clone, Default)]
+pub struct MemIoWriteProvider {
+    buffer: LineBuffer,
+}
+
+impl IoWriter for MemIoWriteProvider {
+    fn write_line(&mut self, content: &str) -> io::Result<()> {
+        self.buffer.borrow_mut().push(content.to_string());
+        Ok(())
+    }
+}
+
+impl MemIoWriteProvider {
+    /// writer appending lines to buffer
+    pub fn new_writer(buffer: &LineBuffer) -> Box<dyn IoWriter> {
+        Box::new(MemIoWriteProvider {
+            buffer: Rc::clone(buffer),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn read_from_text_and_lines() {
+        let mut reader = MemIoReadProvider::from_text("3\r\n1 2 3\n\nend");
+        assert_eq!(reader.read_line().unwrap().as_deref(), Some("3"));
+        assert_eq!(reader.read_i64s().unwrap(), Some(vec![1, 2, 3]));
+        assert_eq!(reader.read_line().unwrap().as_deref(), Some(""));
+        assert_eq!(reader.read_line().unwrap().as_deref(), Some("end"));
+        assert_eq!(reader.read_line().unwrap(), None);
+
+        let mut reader = MemIoReadProvider::from_lines(vec!["a".to_string()]);
+        assert_eq!(reader.read_line().unwrap().as_deref(), Some("a"));
+        assert_eq!(reader.read_line().unwrap(), None);
+    }
+
+    #[test]
+    fn write_to_shared_buffer() {
+        let buffer = LineBuffer::default();
+        let mut writer = MemIoWriteProvider::new_writer(&buffer);
+        writer.write_line("first").unwrap();
+        writer.write_line("second").unwrap();
+        assert_eq!(*buffer.borrow(), ["first", "second"]);
+    }
+}
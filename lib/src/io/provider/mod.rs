@@ -1,2 +1,10 @@
+pub mod adapter;
+pub mod archio;
+pub mod fastio;
 pub mod fileio;
+mod lines;
+pub mod memio;
 pub mod stdio;
+
+#[cfg(test)]
+mod conformance;
@@ -1,53 +1,64 @@
-use std::io::{stdin, stdout, BufRead, Write};
+use std::io::{self, stderr, stdin, stdout, Write};
 
+use crate::io::provider::lines::LineBuf;
 use crate::io::{IoReader, IoWriter};
 
 pub struct StdIoProvider {
     multi_threads: bool,
+    line: LineBuf,
 }
 
 impl IoReader for StdIoProvider {
-    fn read_line(&mut self) -> Option<String> {
-        let mut input_string = String::new();
-        if self.multi_threads {
-            stdin()
-                .lock()
-                .read_line(&mut input_string)
-                .expect("err: input please...");
-        } else {
-            stdin()
-                .read_line(&mut input_string)
-                .expect("error: input please...");
-        }
-        Some(input_string)
+    fn read_bytes(&mut self) -> io::Result<Option<Vec<u8>>> {
+        Ok(self.line.next(&mut stdin().lock())?.map(<[u8]>::to_vec))
+    }
+
+    fn peek_bytes(&mut self) -> io::Result<Option<&[u8]>> {
+        self.line.peek(&mut stdin().lock())
     }
 }
 
 impl IoWriter for StdIoProvider {
-    fn write_line(&mut self, content: &str) -> Option<bool> {
+    fn write_line(&mut self, content: &str) -> io::Result<()> {
         if self.multi_threads {
-            stdout()
-                .lock()
-                .write_fmt(format_args!("{}\n", content))
-                .expect("err: output please...");
+            writeln!(stdout().lock(), "{}", content)
         } else {
-            stdout()
-                .write_fmt(format_args!("{}\n", content))
-                .expect("err: output please...");
+            writeln!(stdout(), "{}", content)
         }
-        Some(true)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        stdout().flush()
+    }
+}
+
+/// Writer to stderr, eg. logs kept apart from answers in stdout.
+pub struct StdErrWriter;
+
+impl IoWriter for StdErrWriter {
+    fn write_line(&mut self, content: &str) -> io::Result<()> {
+        writeln!(stderr().lock(), "{}", content)
     }
 }
 
 impl StdIoProvider {
     pub fn new_reader(multi_threads: bool) -> Option<Box<dyn IoReader>> {
-        let std_io_provider = StdIoProvider { multi_threads };
+        let std_io_provider = StdIoProvider::new(multi_threads);
         Some(Box::new(std_io_provider))
     }
     pub fn new_writer(multi_threads: bool) -> Option<Box<dyn IoWriter>> {
-        let std_io_provider = StdIoProvider { multi_threads };
+        let std_io_provider = StdIoProvider::new(multi_threads);
         Some(Box::new(std_io_provider))
     }
+    pub fn new_err_writer() -> Option<Box<dyn IoWriter>> {
+        Some(Box::new(StdErrWriter))
+    }
+    fn new(multi_threads: bool) -> StdIoProvider {
+        StdIoProvider {
+            multi_threads,
+            line: LineBuf::default(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -70,6 +81,12 @@ mod tests {
         assert!(std_io_writer_pack.is_some());
         let mut std_io_writer = std_io_writer_pack.unwrap();
         let result = std_io_writer.write_line("Hello World");
-        assert!(result.is_some());
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn std_err_writer_provider() {
+        let mut std_err_writer = StdIoProvider::new_err_writer().unwrap();
+        assert!(std_err_writer.write_line("Hello stderr").is_ok());
     }
 }
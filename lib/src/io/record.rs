@@ -0,0 +1,125 @@
+/// Log records and their output formats
+/// Defines:
+///   LogRecord : a log message with level, time, context and key/value fields.
+///   LogFormat : text line with [[...]] markup, or a JSON line for post-processing.
+/// JSON line(one object per record, markup stripped from msg):
+///   {"ts":1700000000000,"level":"INFO","question":"03s3","testcase":"ccc2003s3.tc",
+///    "span":"03s3/ccc2003s3.tc/solve","msg":"room found","fields":{"size":"12"}}
+///   ts is milliseconds since unix epoch, question, testcase and span are null if not set.
+///
+use crate::io::debug::DebugLevel;
+use crate::io::markup;
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Output format of a sink
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRecord<'a> {
+    pub ts: SystemTime,
+    pub level: DebugLevel,
+    pub question: Option<&'a str>,
+    pub testcase: Option<&'a str>,
+    pub span: Option<&'a str>, // path of entered spans
+    pub msg: &'a str,
+    pub fields: &'a [(&'a str, &'a str)],
+}
+
+impl LogRecord<'_> {
+    /// A JSON object in a single line
+    pub fn to_json(&self) -> String {
+        let ts = self
+            .ts
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let mut json = format!("{{\"ts\":{},\"level\":", ts);
+        push_json_str(&mut json, &self.level.to_string());
+        let context = [
+            ("question", self.question),
+            ("testcase", self.testcase),
+            ("span", self.span),
+        ];
+        for (key, value) in context {
+            let _ = write!(json, ",\"{}\":", key);
+            match value {
+                Some(value) => push_json_str(&mut json, value),
+                None => json.push_str("null"),
+            }
+        }
+        json.push_str(",\"msg\":");
+        push_json_str(&mut json, &markup::render(self.msg, false));
+        json.push_str(",\"fields\":{");
+        for (i, (key, value)) in self.fields.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            push_json_str(&mut json, key);
+            json.push(':');
+            push_json_str(&mut json, value);
+        }
+        json.push_str("}}");
+        json
+    }
+}
+
+/// Append s as a quoted JSON string
+fn push_json_str(json: &mut String, s: &str) {
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn record_to_json() {
+        let record = LogRecord {
+            ts: UNIX_EPOCH + Duration::from_millis(1700000000123),
+            level: DebugLevel::Info,
+            question: Some("03s3"),
+            testcase: None,
+            span: Some("03s3/solve"),
+            msg: "room [[found]]",
+            fields: &[("size", "12"), ("note", "a \"b\"\\\n\u{1}")],
+        };
+        assert_eq!(
+            record.to_json(),
+            concat!(
+                r#"{"ts":1700000000123,"level":"INFO","question":"03s3","testcase":null,"#,
+                r#""span":"03s3/solve","msg":"room found","fields":{"size":"12","note":"a \"b\"\\\n\u0001"}}"#
+            )
+        );
+        let record = LogRecord {
+            level: DebugLevel::Answer,
+            testcase: Some("ccc2003s3.tc"),
+            fields: &[],
+            ..record
+        };
+        assert!(record
+            .to_json()
+            .contains(r#""level":"ANSW","question":"03s3","testcase":"ccc2003s3.tc","span""#));
+        assert!(record.to_json().ends_with(r#""fields":{}}"#));
+    }
+}
@@ -0,0 +1,300 @@
+/// Token Scanner over IoReader
+/// Defines:
+///   token     : whitespace separated word, may cross line boundaries.
+///   line mode : read_line returns the rest of the current line if any token left in it,
+///               otherwise the next line. token and line reads can be mixed.
+///   bytes     : next_bytes and next_i64 work on the line buffer, no String per line or token.
+/// Sample(03s3 floor plan):
+///   let mut sc = Scanner::new(reader);
+///   let wood: i32 = sc.next()?.unwrap();
+///   let (rows, cols) = (sc.next::<usize>()?.unwrap(), sc.next::<usize>()?.unwrap());
+///   let plan = sc.next_grid(rows, cols)?.unwrap();
+/// Errors:
+///   Ok(None) at end of input, InvalidData error for a token or line not as expected,
+///   and read errors of reader are passed through.
+///
+use crate::io::provider::fastio::FastIoReadProvider;
+use crate::io::provider::fileio::parse_i64;
+use crate::io::provider::stdio::StdIoProvider;
+use crate::io::IoReader;
+use std::any::type_name;
+use std::io;
+use std::str::{self, FromStr};
+
+pub struct Scanner {
+    reader: Box<dyn IoReader>,
+    line: Vec<u8>, // current line, buffer reused for next lines
+    pos: usize,    // byte offset of unread rest in current line
+}
+
+impl Scanner {
+    pub fn new(reader: Box<dyn IoReader>) -> Scanner {
+        Scanner {
+            reader,
+            line: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Scanner over judge stdin
+    pub fn stdin() -> Scanner {
+        Scanner::new(StdIoProvider::new_reader(true).unwrap())
+    }
+
+    /// Scanner over judge stdin read at once, for large inputs
+    pub fn stdin_fast() -> io::Result<Scanner> {
+        Ok(Scanner::new(FastIoReadProvider::stdin()?))
+    }
+
+    /// Next whitespace separated token, None at end of input
+    pub fn next_token(&mut self) -> io::Result<Option<String>> {
+        Ok(self
+            .next_bytes()?
+            .map(|token| String::from_utf8_lossy(token).into_owned()))
+    }
+
+    /// Next token's raw bytes, a slice of current line without allocation.
+    pub fn next_bytes(&mut self) -> io::Result<Option<&[u8]>> {
+        let Some((start, end)) = self.token_span()? else {
+            return Ok(None);
+        };
+        self.pos = end;
+        Ok(Some(&self.line[start..end]))
+    }
+
+    /// Next token as i64, parsed from bytes without UTF-8 check or allocation.
+    ///   InvalidData error if token is not an i64, the token is not consumed then.
+    pub fn next_i64(&mut self) -> io::Result<Option<i64>> {
+        let Some((start, end)) = self.token_span()? else {
+            return Ok(None);
+        };
+        let token = &self.line[start..end];
+        let value = parse_i64(token).ok_or_else(|| bad_token("i64", token))?;
+        self.pos = end;
+        Ok(Some(value))
+    }
+
+    /// Next token parsed as T, None at end of input.
+    ///   InvalidData error if token is not a T, the token is not consumed then.
+    ///   typed by caller, so not an Iterator.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next<T: FromStr>(&mut self) -> io::Result<Option<T>> {
+        let Some((start, end)) = self.token_span()? else {
+            return Ok(None);
+        };
+        let token = &self.line[start..end];
+        let value = str::from_utf8(token)
+            .ok()
+            .and_then(|token| token.parse().ok())
+            .ok_or_else(|| bad_token(type_name::<T>(), token))?;
+        self.pos = end;
+        Ok(Some(value))
+    }
+
+    /// Next n tokens parsed as T, may cross line boundaries.
+    ///   None if input ends before n tokens.
+    pub fn next_n<T: FromStr>(&mut self, n: usize) -> io::Result<Option<Vec<T>>> {
+        (0..n).map(|_| self.next()).collect()
+    }
+
+    /// Character grid in rows lines, each line has cols chars at least.
+    ///   spaces inside a row are kept as grid chars.
+    ///   None if input ends before rows lines, InvalidData error if a line is too short.
+    pub fn next_grid(&mut self, rows: usize, cols: usize) -> io::Result<Option<Vec<Vec<char>>>> {
+        let mut grid = Vec::new();
+        for _ in 0..rows {
+            let Some(line) = self.read_line()? else {
+                return Ok(None);
+            };
+            let row: Vec<char> = line.chars().take(cols).collect();
+            if row.len() < cols {
+                return Err(bad_token(&format!("{} chars", cols), line.as_bytes()));
+            }
+            grid.push(row);
+        }
+        Ok(Some(grid))
+    }
+
+    /// Line mode read: rest of the current line, or the next line if nothing left in it.
+    ///   line ending is removed, non UTF-8 bytes are replaced with U+FFFD.
+    pub fn read_line(&mut self) -> io::Result<Option<String>> {
+        Ok(self
+            .take_line()?
+            .map(|line| String::from_utf8_lossy(line).into_owned()))
+    }
+
+    /// Line mode read of raw bytes, see read_line
+    fn take_line(&mut self) -> io::Result<Option<&[u8]>> {
+        let rest = &self.line[self.pos..];
+        if !rest.trim_ascii().is_empty() {
+            let start = self.line.len() - rest.trim_ascii_start().len();
+            self.pos = self.line.len();
+            return Ok(Some(&self.line[start..]));
+        }
+        if !self.fetch_line()? {
+            return Ok(None);
+        }
+        self.pos = self.line.len();
+        Ok(Some(&self.line))
+    }
+
+    /// Byte range of next token in current line, lines without tokens are skipped.
+    fn token_span(&mut self) -> io::Result<Option<(usize, usize)>> {
+        loop {
+            let rest = &self.line[self.pos..];
+            if let Some(skip) = rest.iter().position(|b| !b.is_ascii_whitespace()) {
+                let start = self.pos + skip;
+                let len = self.line[start..]
+                    .iter()
+                    .position(u8::is_ascii_whitespace)
+                    .unwrap_or(self.line.len() - start);
+                return Ok(Some((start, start + len)));
+            }
+            if !self.fetch_line()? {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Copy next line from reader into current line buffer, false at end of input
+    fn fetch_line(&mut self) -> io::Result<bool> {
+        let Some(bytes) = self.reader.peek_bytes()? else {
+            return Ok(false);
+        };
+        self.line.clear();
+        self.line.extend_from_slice(bytes);
+        self.reader.skip_line()?;
+        self.pos = 0;
+        Ok(true)
+    }
+}
+
+/// InvalidData error of a token not as expected
+fn bad_token(expect: &str, token: &[u8]) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!(
+            "expect {}, found '{}'",
+            expect,
+            String::from_utf8_lossy(token)
+        ),
+    )
+}
+
+/// Line mode reads as IoReader
+impl IoReader for Scanner {
+    fn read_bytes(&mut self) -> io::Result<Option<Vec<u8>>> {
+        Ok(self.take_line()?.map(<[u8]>::to_vec))
+    }
+
+    fn peek_bytes(&mut self) -> io::Result<Option<&[u8]>> {
+        let rest = &self.line[self.pos..];
+        if !rest.trim_ascii().is_empty() {
+            return Ok(Some(self.line[self.pos..].trim_ascii_start()));
+        }
+        self.reader.peek_bytes()
+    }
+
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        Scanner::read_line(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io::provider::memio::MemIoReadProvider;
+
+    fn scanner(lines: &[&str]) -> Scanner {
+        Scanner::new(MemIoReadProvider::from_lines(
+            lines.iter().map(|l| l.to_string()).collect(),
+        ))
+    }
+
+    /// reader failing after its lines
+    struct FailingReader(Box<dyn IoReader>);
+
+    impl IoReader for FailingReader {
+        fn read_bytes(&mut self) -> io::Result<Option<Vec<u8>>> {
+            match self.0.read_bytes()? {
+                Some(line) => Ok(Some(line)),
+                None => Err(io::Error::other("disk gone")),
+            }
+        }
+
+        fn peek_bytes(&mut self) -> io::Result<Option<&[u8]>> {
+            match self.0.peek_bytes()? {
+                Some(line) => Ok(Some(line)),
+                None => Err(io::Error::other("disk gone")),
+            }
+        }
+    }
+
+    #[test]
+    fn scan_tokens_across_lines() {
+        let mut sc = scanner(&["3 1 2", "", "  3 4  ", "5\r\n", "x"]);
+        assert_eq!(sc.next::<i32>().unwrap(), Some(3));
+        assert_eq!(sc.next_n::<i64>(5).unwrap(), Some(vec![1, 2, 3, 4, 5]));
+        let err = sc.next::<i32>().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "expect i32, found 'x'");
+        assert_eq!(sc.next_token().unwrap().as_deref(), Some("x"));
+        assert_eq!(sc.next::<i32>().unwrap(), None);
+        assert_eq!(sc.next_token().unwrap(), None);
+        let mut sc = scanner(&["1 2", "3"]);
+        assert_eq!(sc.next_n::<u8>(4).unwrap(), None);
+    }
+
+    #[test]
+    fn scan_bytes_and_i64s() {
+        let mut sc = scanner(&["-9223372036854775808 +7", "  ab\u{e9}  x1 5"]);
+        assert_eq!(sc.next_i64().unwrap(), Some(i64::MIN));
+        assert_eq!(sc.next_i64().unwrap(), Some(7));
+        assert_eq!(sc.next_bytes().unwrap(), Some("ab\u{e9}".as_bytes()));
+        let err = sc.next_i64().unwrap_err();
+        assert_eq!(err.to_string(), "expect i64, found 'x1'");
+        assert_eq!(sc.next_bytes().unwrap(), Some(&b"x1"[..]));
+        assert_eq!(sc.next_i64().unwrap(), Some(5));
+        assert_eq!(sc.next_i64().unwrap(), None);
+        assert_eq!(sc.next_bytes().unwrap(), None);
+    }
+
+    #[test]
+    fn scan_read_error_is_not_eof() {
+        let lines = vec!["1".to_string()];
+        let mut sc = Scanner::new(Box::new(FailingReader(MemIoReadProvider::from_lines(
+            lines,
+        ))));
+        assert_eq!(sc.next::<i32>().unwrap(), Some(1));
+        assert_eq!(sc.next::<i32>().unwrap_err().to_string(), "disk gone");
+        assert!(IoReader::read_line(&mut sc).is_err());
+    }
+
+    #[test]
+    fn scan_grid_and_lines() {
+        let mut sc = scanner(&[
+            "105",
+            "3 4",
+            "I..I",
+            "I I.",
+            "IIII",
+            "rest of  line",
+            "next",
+        ]);
+        assert_eq!(sc.next::<i32>().unwrap(), Some(105));
+        let rows = sc.next::<usize>().unwrap().unwrap();
+        let cols = sc.next::<usize>().unwrap().unwrap();
+        let grid = sc.next_grid(rows, cols).unwrap().unwrap();
+        assert_eq!(grid[0], ['I', '.', '.', 'I']);
+        assert_eq!(grid[1], ['I', ' ', 'I', '.']);
+        assert_eq!(sc.next_token().unwrap().as_deref(), Some("rest"));
+        assert_eq!(sc.read_line().unwrap().as_deref(), Some("of  line"));
+        assert_eq!(sc.read_line().unwrap().as_deref(), Some("next"));
+        assert_eq!(sc.read_line().unwrap(), None);
+        let mut sc = scanner(&["abc"]);
+        let err = sc.next_grid(1, 4).unwrap_err();
+        assert_eq!(err.to_string(), "expect 4 chars, found 'abc'");
+        let mut sc = scanner(&["abcd"]);
+        assert_eq!(sc.next_grid(2, 4).unwrap(), None);
+    }
+}
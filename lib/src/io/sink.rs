@@ -0,0 +1,209 @@
+/// Log sinks of Logger
+/// Defines:
+///   LogSink     : a destination of logs, with its own level range and color policy.
+///   ColorPolicy : colored or plain text of a sink.
+///   LogFormat   : text or JSON lines, see io::record.
+/// Sample(answers to file, logs to stderr):
+///   let logger = Logger::with_sinks(vec![
+///       LogSink::stderr(DebugLevel::Info).up_to(DebugLevel::Error),
+///       LogSink::file("answers.txt", DebugLevel::Answer)?,
+///   ]);
+///
+use crate::io::debug::DebugLevel;
+use crate::io::provider::fileio::FileIoWriteProvider;
+use crate::io::provider::memio::{LineBuffer, MemIoWriteProvider};
+use crate::io::provider::stdio::StdIoProvider;
+use crate::io::record::LogFormat;
+use crate::io::IoWriter;
+use std::io::{self, stderr, stdout, IsTerminal};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{env, fs};
+
+/// Color policy of a sink
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum ColorPolicy {
+    /// colored if sink is a terminal and NO_COLOR is not set
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorPolicy {
+    fn colored(&self, terminal: bool) -> bool {
+        match self {
+            ColorPolicy::Auto => terminal && env::var_os("NO_COLOR").is_none(),
+            ColorPolicy::Always => true,
+            ColorPolicy::Never => false,
+        }
+    }
+}
+
+pub struct LogSink {
+    output_handle: Box<dyn IoWriter>,
+    trigger_level: DebugLevel,
+    max_level: DebugLevel,
+    terminal: bool,
+    colored: bool,
+    format: LogFormat,
+}
+
+impl LogSink {
+    /// Sink of any writer, levels from trigger_level, plain text
+    pub fn new(output_handle: Box<dyn IoWriter>, trigger_level: DebugLevel) -> LogSink {
+        LogSink {
+            output_handle,
+            trigger_level,
+            max_level: DebugLevel::Answer,
+            terminal: false,
+            colored: false,
+            format: LogFormat::Text,
+        }
+    }
+
+    /// Sink of stdout, colored by ColorPolicy::Auto
+    pub fn stdout(trigger_level: DebugLevel) -> LogSink {
+        let writer = StdIoProvider::new_writer(true).unwrap();
+        LogSink::terminal(writer, trigger_level, stdout().is_terminal())
+    }
+
+    /// Sink of stderr, colored by ColorPolicy::Auto
+    pub fn stderr(trigger_level: DebugLevel) -> LogSink {
+        let writer = StdIoProvider::new_err_writer().unwrap();
+        LogSink::terminal(writer, trigger_level, stderr().is_terminal())
+    }
+
+    /// Sink of a file, created or truncated, each line is flushed once written
+    pub fn file<P: AsRef<Path>>(path: P, trigger_level: DebugLevel) -> io::Result<LogSink> {
+        let file_path = path.as_ref().to_string_lossy();
+        let writer = FileIoWriteProvider::open_for_write(&file_path)?;
+        Ok(LogSink::new(writer, trigger_level))
+    }
+
+    /// Sink of in-memory lines, eg. for tests
+    pub fn memory(buffer: &LineBuffer, trigger_level: DebugLevel) -> LogSink {
+        LogSink::new(MemIoWriteProvider::new_writer(buffer), trigger_level)
+    }
+
+    /// Sink of a new file per run in dir, named "{prefix}-{millis}.log".
+    ///   only the newest keep files of prefix are kept, older ones are removed.
+    pub fn rolling<P: AsRef<Path>>(
+        dir: P,
+        prefix: &str,
+        keep: usize,
+        trigger_level: DebugLevel,
+    ) -> io::Result<LogSink> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let sink = LogSink::file(
+            dir.join(format!("{}-{:016}.log", prefix, millis)),
+            trigger_level,
+        )?;
+        let mut runs: Vec<_> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .filter(|name| name.starts_with(&format!("{}-", prefix)) && name.ends_with(".log"))
+            .collect();
+        runs.sort();
+        for name in runs.iter().rev().skip(keep.max(1)) {
+            fs::remove_file(dir.join(name))?;
+        }
+        Ok(sink)
+    }
+
+    fn terminal(writer: Box<dyn IoWriter>, trigger_level: DebugLevel, terminal: bool) -> LogSink {
+        LogSink {
+            terminal,
+            ..LogSink::new(writer, trigger_level)
+        }
+        .with_color(ColorPolicy::Auto)
+    }
+
+    /// Only levels up to max_level, eg. up_to(DebugLevel::Error) for logs without answers
+    pub fn up_to(mut self, max_level: DebugLevel) -> LogSink {
+        self.max_level = max_level;
+        self
+    }
+
+    pub fn with_color(mut self, policy: ColorPolicy) -> LogSink {
+        self.colored = policy.colored(self.terminal);
+        self
+    }
+
+    /// JSON lines are never colored
+    pub fn with_format(mut self, format: LogFormat) -> LogSink {
+        self.format = format;
+        self
+    }
+
+    pub fn format(&self) -> LogFormat {
+        self.format
+    }
+
+    pub fn colored(&self) -> bool {
+        self.colored
+    }
+
+    pub fn can_trigger(&self, lvl: &DebugLevel) -> bool {
+        self.trigger_level.can_trigger(lvl) && *lvl <= self.max_level
+    }
+
+    pub fn write_line(&mut self, line: &str) -> io::Result<()> {
+        self.output_handle.write_line(line)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.output_handle.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::process;
+
+    #[test]
+    fn sink_level_range() {
+        let sink = LogSink::memory(&LineBuffer::default(), DebugLevel::Warn);
+        assert!(!sink.can_trigger(&DebugLevel::Info));
+        assert!(sink.can_trigger(&DebugLevel::Warn));
+        assert!(sink.can_trigger(&DebugLevel::Answer));
+        let sink = sink.up_to(DebugLevel::Error);
+        assert!(sink.can_trigger(&DebugLevel::Error));
+        assert!(!sink.can_trigger(&DebugLevel::Answer));
+    }
+
+    #[test]
+    fn sink_color_policy() {
+        let sink = LogSink::memory(&LineBuffer::default(), DebugLevel::Info);
+        assert!(!sink.colored());
+        let sink = sink.with_color(ColorPolicy::Always);
+        assert!(sink.colored());
+        let sink = sink.with_color(ColorPolicy::Auto);
+        assert!(!sink.colored());
+    }
+
+    #[test]
+    fn rolling_keeps_newest_runs() {
+        let dir = env::temp_dir().join(format!("ccc_sink_rolling_{}", process::id()));
+        for run in 0..3 {
+            let mut sink = LogSink::rolling(&dir, "run", 2, DebugLevel::Info).unwrap();
+            sink.write_line(&format!("run {}", run)).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        let mut names: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        names.sort();
+        assert_eq!(names.len(), 2);
+        assert_eq!(fs::read_to_string(&names[0]).unwrap(), "run 1\n");
+        assert_eq!(fs::read_to_string(&names[1]).unwrap(), "run 2\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -20,7 +20,7 @@
 use std::cmp;
 
 /// Table Border Char List
-pub const TABLE_BORDER_CHARS: [&'static str; 17] = [
+pub const TABLE_BORDER_CHARS: [&str; 17] = [
     "┌", "┐", "└", "┘", // LT, RT, LB, RB <- Corner
     "├", "┤", "┬", "┴", // LM, RM, TM, BM <- Seperator Border
     "│", "│", "─", "─", // LL, RR, TT, BB <- Border
@@ -94,7 +94,7 @@ fn cell_patterns(
     pos_group: TblGrp,
     column_type: ColumnType,
 ) -> (&'static str, &'static str, &'static str, &'static str) {
-    let patterns = match (pos_group, column_type) {
+    match (pos_group, column_type) {
         (TblGrp::HeadTop, ColumnType::LineNum) => ("", "", bdr!(CM), bdr!(CM)), // LineNum
         (TblGrp::HeadTop, ColumnType::First) => (bdr!(LT), bdr!(TM), bdr!(TT), bdr!(TT)), // First
         (TblGrp::HeadTop, ColumnType::Middle) => ("", bdr!(TM), bdr!(TT), bdr!(TT)), // Middle
@@ -120,8 +120,7 @@ fn cell_patterns(
         (TblGrp::Summary, ColumnType::Middle) => ("", bdr!(CM), bdr!(CM), bdr!(CM)), // Middle
         (TblGrp::Summary, ColumnType::Last) => ("", bdr!(CM), bdr!(CM), bdr!(CM)), // Last
         (_, _) => ("", "", "", ""),
-    };
-    patterns
+    }
 }
 
 /// Table Border Char Group
@@ -286,7 +285,7 @@ impl TableTool {
 
     /// Get Table Border String.
     pub fn char_at_tblpos(pos: TablePos) -> &'static str {
-        return TABLE_BORDER_CHARS[pos as usize];
+        TABLE_BORDER_CHARS[pos as usize]
     }
 
     /// Make cell format string for formatting
@@ -300,10 +299,7 @@ impl TableTool {
             1 if self.tbl_cfg.has_linenum => ColumnType::First,
             idx if idx < self.tbl_cfg.columns.len() - 1 => ColumnType::Middle,
             idx if idx == self.tbl_cfg.columns.len() - 1 => ColumnType::Last,
-            _ => {
-                assert!(false);
-                ColumnType::Middle
-            }
+            _ => unreachable!(),
         };
 
         // choice cell format pattern item: left_border, right_border, left_margin, right_margin
@@ -332,7 +328,7 @@ impl TableTool {
             if need_fill_border {
                 rdr_txt = format!("{:width$}", "", width = col_width);
             } else {
-                if rdr_txt == "" {
+                if rdr_txt.is_empty() {
                     rdr_txt = format!("{:>width$}", rdr_txt, width = col_width);
                 } else {
                     rdr_txt = format!("{:0>width$}", rdr_txt, width = col_width);
@@ -370,7 +366,7 @@ impl TableTool {
         );
         let mut line_render = String::new();
         for (idx, _column) in self.tbl_cfg.columns.iter().enumerate() {
-            let line = self.fmt_cell(tbl_grp, idx, &all_cells[idx]);
+            let line = self.fmt_cell(tbl_grp, idx, all_cells[idx]);
             line_render.push_str(line.as_str());
         }
         line_render
@@ -458,7 +454,7 @@ impl TableTool {
         };
         let mut line_render = String::new();
         for (idx, _column) in self.tbl_cfg.columns.iter().enumerate() {
-            let line = self.fmt_cell(tbl_grp, idx, &cell_text_list[idx]);
+            let line = self.fmt_cell(tbl_grp, idx, cell_text_list[idx]);
             line_render.push_str(line.as_str());
         }
         line_render
@@ -487,7 +483,7 @@ mod test {
 
         assert_eq!(tbl_cfg.columns.len(), 4);
 
-        let col = tbl_cfg.columns.get(0).unwrap();
+        let col = tbl_cfg.columns.first().unwrap();
         assert_eq!(col.width, 10);
         assert_eq!(col.title, "First Column");
         assert_eq!(col.align, ColumnAlign::Left);